@@ -30,7 +30,7 @@ async fn main() -> Result<()> {
     if let Some(processed_template) = pm.get_processed_template(8) { // Index 8 is our new template
         println!("Processed template: {}", processed_template);
         
-        // Generate response (this will be a mock response in current implementation)
+        // Generate response (requires a valid API key)
         match llm.generate_response(&processed_template).await {
             Ok(response) => println!("LLM Response: {}", response),
             Err(e) => println!("Error: {}", e),
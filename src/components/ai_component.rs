@@ -9,13 +9,13 @@ pub trait AIComponent: Debug + Send + Sync {
     /// 
     /// This method should be called before using the component
     /// to set up any necessary resources or connections.
-    fn initialize(&mut self) -> anyhow::Result<()>;
+    fn initialize(&mut self) -> crate::Result<()>;
     
     /// Process the component's main functionality
     /// 
     /// This method should implement the main processing logic
     /// for the component.
-    fn process(&self) -> anyhow::Result<()>;
+    fn process(&self) -> crate::Result<()>;
     
     /// Get the component name
     fn name(&self) -> &str;
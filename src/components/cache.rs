@@ -0,0 +1,320 @@
+use crate::components::llm_interface::{ChatRequest, Completion, LLMConfig, Message, ResponseSchema, Tool};
+use crate::components::providers::{AzureOptions, OllamaOptions, ProviderKind};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+/// Parts of a request that decide its response
+#[derive(Serialize)]
+struct CacheKey<'a> {
+    provider: ProviderKind,
+    base_url: Option<&'a str>,
+    model: &'a str,
+    messages: &'a [Message],
+    tools: &'a [Tool],
+    response_schema: Option<&'a ResponseSchema>,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+    ollama: &'a OllamaOptions,
+    azure: &'a AzureOptions,
+}
+
+/// Stored completion with the time it was written
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    stored_at: u64,
+    completion: Completion,
+}
+
+/// Write order of the entries, used to evict the oldest
+/// 
+/// Built from file modification times when the cache is opened, so a full
+/// cache never has to read its entries back to find the oldest one.
+#[derive(Debug, Default)]
+struct EntryIndex {
+    order: BTreeMap<(SystemTime, u64), String>,
+    keys: HashMap<String, (SystemTime, u64)>,
+    next: u64,
+}
+
+impl EntryIndex {
+    /// Record a write to `key`, making it the newest entry
+    fn insert(&mut self, key: String, written: SystemTime) {
+        self.remove(&key);
+        let position = (written, self.next);
+        self.next += 1;
+        self.order.insert(position, key.clone());
+        self.keys.insert(key, position);
+    }
+    
+    fn remove(&mut self, key: &str) {
+        if let Some(position) = self.keys.remove(key) {
+            self.order.remove(&position);
+        }
+    }
+    
+    /// Take the oldest key
+    fn pop_oldest(&mut self) -> Option<String> {
+        let (_, key) = self.order.pop_first()?;
+        self.keys.remove(&key);
+        Some(key)
+    }
+}
+
+/// On-disk cache of completions keyed by request content
+/// 
+/// Each entry is a JSON file in the cache directory named after the hash
+/// of the provider, model, messages, tools, schema, temperature, output
+/// limit and provider options such as the Ollama seed or the Azure
+/// deployment. Since sampled replies differ from call to call, the cache
+/// is only used for requests with a temperature of 0 unless caching is
+/// forced. Wrap it in an `Arc` to share it between interfaces.
+#[derive(Debug)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    temporary: bool,
+    ttl: Option<Duration>,
+    max_entries: Option<usize>,
+    index: Mutex<EntryIndex>,
+    forced: AtomicBool,
+    bypass: AtomicBool,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ResponseCache {
+    /// Open or create a cache in the directory at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let dir = path.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(|e| cache_error(&dir, e))?;
+        
+        let mut entries = Vec::new();
+        for path in entry_paths(&dir)? {
+            let written = fs::metadata(&path).and_then(|metadata| metadata.modified()).unwrap_or(UNIX_EPOCH);
+            if let Some(key) = path.file_stem().and_then(|stem| stem.to_str()) {
+                entries.push((written, key.to_string()));
+            }
+        }
+        entries.sort();
+        let mut index = EntryIndex::default();
+        for (written, key) in entries {
+            index.insert(key, written);
+        }
+        
+        Ok(Self {
+            dir,
+            temporary: false,
+            ttl: None,
+            max_entries: None,
+            index: Mutex::new(index),
+            forced: AtomicBool::new(false),
+            bypass: AtomicBool::new(false),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        })
+    }
+    
+    /// Create a cache that is deleted when dropped
+    pub fn temporary() -> Result<Self> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let name = format!("ai-playground-cache-{}-{}", std::process::id(), NEXT.fetch_add(1, Ordering::Relaxed));
+        let mut cache = Self::open(std::env::temp_dir().join(name))?;
+        cache.temporary = true;
+        cache.clear()?;
+        Ok(cache)
+    }
+    
+    /// Expire entries older than `ttl`
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+    
+    /// Keep at most `max_entries` entries, evicting the oldest first
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+    
+    /// Cache requests whatever their temperature
+    pub fn set_forced(&self, forced: bool) {
+        self.forced.store(forced, Ordering::Relaxed);
+    }
+    
+    /// Check if caching is forced
+    pub fn is_forced(&self) -> bool {
+        self.forced.load(Ordering::Relaxed)
+    }
+    
+    /// Skip the cache entirely, neither reading nor writing it
+    pub fn set_bypass(&self, bypass: bool) {
+        self.bypass.store(bypass, Ordering::Relaxed);
+    }
+    
+    /// Check if the cache is bypassed
+    pub fn is_bypassed(&self) -> bool {
+        self.bypass.load(Ordering::Relaxed)
+    }
+    
+    /// Get the number of lookups served from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+    
+    /// Get the number of lookups that had to call the API
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+    
+    /// Get the number of stored entries
+    pub fn len(&self) -> usize {
+        entry_paths(&self.dir).map_or(0, |paths| paths.len())
+    }
+    
+    /// Check if the cache has no entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
+    /// Remove every entry
+    pub fn clear(&self) -> Result<()> {
+        for path in entry_paths(&self.dir)? {
+            remove(&path)?;
+        }
+        *self.index.lock().unwrap() = EntryIndex::default();
+        Ok(())
+    }
+    
+    /// Check if a request with this config should go through the cache
+    pub(crate) fn applies_to(&self, config: &LLMConfig) -> bool {
+        !self.is_bypassed() && (self.is_forced() || config.temperature == Some(0.0))
+    }
+    
+    /// Compute the cache key of a request
+    pub(crate) fn key(config: &LLMConfig, request: &ChatRequest<'_>) -> String {
+        let key = CacheKey {
+            provider: config.provider,
+            base_url: config.base_url.as_deref(),
+            model: &config.model,
+            messages: request.messages,
+            tools: request.tools,
+            response_schema: request.response_schema,
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+            ollama: &config.ollama,
+            azure: &config.azure,
+        };
+        let json = serde_json::to_vec(&key).expect("cache keys always serialize");
+        format!("{:x}", Sha256::digest(json))
+    }
+    
+    /// Look up a completion, counting the hit or miss
+    pub(crate) fn get(&self, key: &str) -> Result<Option<Completion>> {
+        let path = self.entry_path(key);
+        let entry = match fs::read(&path) {
+            Ok(bytes) => Some(serde_json::from_slice::<CacheEntry>(&bytes)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(cache_error(&path, e)),
+        };
+        
+        let expired = |entry: &CacheEntry| {
+            self.ttl.is_some_and(|ttl| now().saturating_sub(entry.stored_at) >= ttl.as_secs())
+        };
+        match entry {
+            Some(entry) if !expired(&entry) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Ok(Some(entry.completion))
+            }
+            Some(_) => {
+                debug!("Cache entry {} expired", key);
+                remove(&path)?;
+                self.index.lock().unwrap().remove(key);
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                Ok(None)
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                Ok(None)
+            }
+        }
+    }
+    
+    /// Store a completion, evicting the oldest entries beyond the size limit
+    pub(crate) fn put(&self, key: &str, completion: &Completion) -> Result<()> {
+        let entry = CacheEntry {
+            stored_at: now(),
+            completion: completion.clone(),
+        };
+        
+        // Write to a scratch file first so readers never see half an entry
+        let path = self.entry_path(key);
+        let scratch = path.with_extension("tmp");
+        fs::write(&scratch, serde_json::to_vec(&entry)?).map_err(|e| cache_error(&scratch, e))?;
+        fs::rename(&scratch, &path).map_err(|e| cache_error(&path, e))?;
+        
+        let mut index = self.index.lock().unwrap();
+        index.insert(key.to_string(), SystemTime::now());
+        if let Some(max_entries) = self.max_entries {
+            while index.keys.len() > max_entries {
+                match index.pop_oldest() {
+                    Some(oldest) => remove(&self.entry_path(&oldest))?,
+                    None => break,
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    
+    /// Path of the file holding the entry for `key`
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+impl Drop for ResponseCache {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+}
+
+/// Paths of every entry stored in `dir`
+fn entry_paths(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = fs::read_dir(dir).map_err(|e| cache_error(dir, e))?;
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| cache_error(dir, e))?.path();
+        if path.extension().is_some_and(|extension| extension == "json") {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+/// Remove an entry file, ignoring one that is already gone
+fn remove(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(cache_error(path, e)),
+        _ => Ok(()),
+    }
+}
+
+fn cache_error(path: &Path, error: io::Error) -> Error {
+    Error::Cache(format!("{}: {}", path.display(), error))
+}
+
+/// Seconds since the Unix epoch
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
@@ -0,0 +1,277 @@
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Request headers that carry credentials
+const SECRET_HEADERS: &[&str] = &["authorization", "x-api-key", "api-key", "x-goog-api-key"];
+
+/// Query parameters that carry credentials
+const SECRET_PARAMS: &[&str] = &["key", "api_key", "api-key"];
+
+/// Response headers that are not replayed
+const SKIPPED_RESPONSE_HEADERS: &[&str] =
+    &["set-cookie", "content-length", "transfer-encoding", "connection", "date"];
+
+/// Placeholder written instead of a secret
+const REDACTED: &str = "[REDACTED]";
+
+/// Whether a cassette captures live traffic or serves it back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send requests to the API and write every exchange to the file
+    Record,
+    /// Serve responses from the file without touching the network
+    Replay,
+}
+
+/// Request as written to the cassette, with credentials redacted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedHttpRequest {
+    method: String,
+    url: String,
+    headers: BTreeMap<String, String>,
+    body: Value,
+}
+
+/// Response as written to the cassette
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedHttpResponse {
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body: String,
+}
+
+/// One request/response exchange
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    /// Hash of the normalized request
+    key: String,
+    request: RecordedHttpRequest,
+    response: RecordedHttpResponse,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Default)]
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    /// How many times each key has been served during replay
+    played: HashMap<String, usize>,
+}
+
+/// Recording of HTTP traffic to provider APIs, in the spirit of Ruby's VCR
+/// 
+/// In record mode every request is sent and the exchange appended to a
+/// JSON file, with API keys redacted. In replay mode responses come from
+/// that file, matched by a hash of the method, path, query and JSON body,
+/// and requests missing from it fail with `Error::CassetteMiss`. Repeated
+/// identical requests replay their recordings in order, then keep
+/// getting the last one.
+/// 
+/// Streamed responses are recorded whole and replayed as one chunk of
+/// events, which the stream parser handles the same way.
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    state: Mutex<CassetteState>,
+}
+
+impl Cassette {
+    /// Start recording to `path`, replacing any earlier recording when the first exchange is saved
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            mode: CassetteMode::Record,
+            state: Mutex::new(CassetteState::default()),
+        }
+    }
+    
+    /// Load a recording from `path` for replay
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| Error::Cassette(format!("{}: {}", path.display(), e)))?;
+        let file: CassetteFile = serde_json::from_str(&contents)
+            .map_err(|e| Error::Cassette(format!("{}: {}", path.display(), e)))?;
+        
+        Ok(Self {
+            path,
+            mode: CassetteMode::Replay,
+            state: Mutex::new(CassetteState {
+                interactions: file.interactions,
+                played: HashMap::new(),
+            }),
+        })
+    }
+    
+    /// Get the cassette mode
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+    
+    /// Get the cassette file path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    
+    /// Get the number of recorded exchanges
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().interactions.len()
+    }
+    
+    /// Check if nothing has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
+    /// Send a request through the cassette
+    pub(crate) async fn execute(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let key = request_key(&request);
+        
+        match self.mode {
+            CassetteMode::Replay => self.find(&key, &request),
+            CassetteMode::Record => {
+                let recorded_request = redact_request(&request);
+                let response = client.execute(request).await?;
+                
+                let recorded_response = RecordedHttpResponse {
+                    status: response.status().as_u16(),
+                    headers: response.headers().iter()
+                        .filter(|(name, _)| !SKIPPED_RESPONSE_HEADERS.contains(&name.as_str()))
+                        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+                        .collect(),
+                    body: response.text().await?,
+                };
+                let replayed = build_response(&recorded_response)?;
+                
+                self.append(Interaction {
+                    key,
+                    request: recorded_request,
+                    response: recorded_response,
+                })?;
+                Ok(replayed)
+            }
+        }
+    }
+    
+    /// Serve the next recording for a request
+    fn find(&self, key: &str, request: &reqwest::Request) -> Result<reqwest::Response> {
+        let mut state = self.state.lock().unwrap();
+        let matches: Vec<usize> = state.interactions.iter()
+            .enumerate()
+            .filter(|(_, interaction)| interaction.key == key)
+            .map(|(index, _)| index)
+            .collect();
+        
+        let Some(&last) = matches.last() else {
+            return Err(Error::CassetteMiss {
+                method: request.method().to_string(),
+                url: redact_url(request.url()).to_string(),
+            });
+        };
+        
+        let played = state.played.entry(key.to_string()).or_insert(0);
+        let index = matches.get(*played).copied().unwrap_or(last);
+        *played += 1;
+        
+        build_response(&state.interactions[index].response)
+    }
+    
+    /// Add an exchange and rewrite the file
+    fn append(&self, interaction: Interaction) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.interactions.push(interaction);
+        
+        let file = CassetteFile {
+            interactions: state.interactions.clone(),
+        };
+        let contents = serde_json::to_string_pretty(&file)?;
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| Error::Cassette(format!("{}: {}", parent.display(), e)))?;
+        }
+        std::fs::write(&self.path, contents)
+            .map_err(|e| Error::Cassette(format!("{}: {}", self.path.display(), e)))
+    }
+}
+
+/// Hash of the parts of a request that decide its response
+/// 
+/// The host is left out so a recording can be replayed against any base
+/// URL, and headers are left out since they mostly carry credentials.
+fn request_key(request: &reqwest::Request) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(request.method().as_str());
+    hasher.update(" ");
+    let url = redact_url(request.url());
+    hasher.update(url.path());
+    hasher.update(url.query().map(|query| format!("?{}", query)).unwrap_or_default());
+    hasher.update("\n");
+    // Re-serializing sorts object keys, so field order does not matter
+    hasher.update(body_value(request).to_string());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Get the request body as JSON, or as a string when it is not JSON
+fn body_value(request: &reqwest::Request) -> Value {
+    let bytes = request.body().and_then(|body| body.as_bytes()).unwrap_or_default();
+    serde_json::from_slice(bytes)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(bytes).into_owned()))
+}
+
+/// Get the URL with credentials in the query string redacted
+fn redact_url(url: &reqwest::Url) -> reqwest::Url {
+    let mut url = url.clone();
+    let pairs: Vec<(String, String)> = url.query_pairs()
+        .map(|(name, value)| {
+            let value = if SECRET_PARAMS.contains(&name.as_ref()) { REDACTED.into() } else { value };
+            (name.into_owned(), value.into_owned())
+        })
+        .collect();
+    
+    if !pairs.is_empty() {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url
+}
+
+/// Copy a request for the cassette with its credentials redacted
+fn redact_request(request: &reqwest::Request) -> RecordedHttpRequest {
+    RecordedHttpRequest {
+        method: request.method().to_string(),
+        url: redact_url(request.url()).to_string(),
+        headers: request.headers().iter()
+            .map(|(name, value)| {
+                let value = if SECRET_HEADERS.contains(&name.as_str()) {
+                    REDACTED.to_string()
+                } else {
+                    value.to_str().unwrap_or_default().to_string()
+                };
+                (name.to_string(), value)
+            })
+            .collect(),
+        body: body_value(request),
+    }
+}
+
+/// Turn a recorded response back into a live one
+fn build_response(recorded: &RecordedHttpResponse) -> Result<reqwest::Response> {
+    let response = recorded.headers.iter()
+        .fold(http::Response::builder().status(recorded.status), |builder, (name, value)| {
+            builder.header(name, value)
+        })
+        .body(recorded.body.clone())
+        .map_err(|e| Error::Cassette(format!("Invalid recorded response: {}", e)))?;
+    
+    Ok(response.into())
+}
@@ -0,0 +1,161 @@
+use crate::components::pricing::{ModelPrice, PriceTable};
+use crate::components::providers::ProviderKind;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Catalog bundled with the crate
+const BUILT_IN_CATALOG: &str = include_str!("models.toml");
+
+/// Optional capability a model may support
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    /// Tool (function) calling
+    Tools,
+    /// Image inputs
+    Vision,
+    /// Schema-constrained JSON output
+    Json,
+    /// Streamed replies
+    Streaming,
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Feature::Tools => "tool calling",
+            Feature::Vision => "image inputs",
+            Feature::Json => "structured JSON output",
+            Feature::Streaming => "streaming",
+        };
+        f.write_str(name)
+    }
+}
+
+/// What is known about a model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub provider: ProviderKind,
+    /// Total tokens the model accepts, prompt and output combined
+    pub context_window: u32,
+    /// Largest `max_tokens` the model accepts
+    pub max_output_tokens: u32,
+    #[serde(default)]
+    pub features: Vec<Feature>,
+    /// Reasoning model, such as the o-series, that takes
+    /// `max_completion_tokens` and no temperature
+    #[serde(default)]
+    pub reasoning: bool,
+    pub price: Option<ModelPrice>,
+}
+
+impl ModelInfo {
+    /// Check if the model supports a feature
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.features.contains(&feature)
+    }
+}
+
+/// Registry of known models
+/// 
+/// The default catalog covers the common OpenAI and Anthropic models.
+/// More models can be added in code or loaded from TOML files such as:
+/// 
+/// ```toml
+/// [models."my-fine-tune"]
+/// provider = "openai"
+/// context_window = 16385
+/// max_output_tokens = 4096
+/// features = ["tools", "streaming"]
+/// price = { input = 3.0, output = 6.0 }
+/// ```
+/// 
+/// Lookups fall back to the longest name followed by a `-` in the model
+/// name, so dated snapshots such as `gpt-4o-2024-08-06` use the entry of
+/// their family while `gpt-4.5-preview` does not match `gpt-4`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelCatalog {
+    #[serde(default)]
+    models: HashMap<String, ModelInfo>,
+}
+
+impl ModelCatalog {
+    /// Get the catalog bundled with the crate
+    pub fn built_in() -> &'static ModelCatalog {
+        static CATALOG: OnceLock<ModelCatalog> = OnceLock::new();
+        CATALOG.get_or_init(|| {
+            ModelCatalog::from_toml_str(BUILT_IN_CATALOG).expect("built-in model catalog is valid")
+        })
+    }
+    
+    /// Create a catalog with no models
+    pub fn empty() -> Self {
+        Self::default()
+    }
+    
+    /// Parse a catalog from TOML
+    pub fn from_toml_str(toml: &str) -> Result<Self> {
+        toml::from_str(toml).map_err(|e| Error::InvalidCatalog(e.to_string()))
+    }
+    
+    /// Load a catalog from a TOML file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let toml = std::fs::read_to_string(path)
+            .map_err(|e| Error::InvalidCatalog(format!("{}: {}", path.display(), e)))?;
+        Self::from_toml_str(&toml)
+    }
+    
+    /// Add the models of a TOML file, replacing entries with the same name
+    pub fn extend_from_file(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        self.extend(Self::load(path)?);
+        Ok(())
+    }
+    
+    /// Add the models of another catalog, replacing entries with the same name
+    pub fn extend(&mut self, other: ModelCatalog) {
+        self.models.extend(other.models);
+    }
+    
+    /// Add or replace a model
+    pub fn insert(&mut self, model: impl Into<String>, info: ModelInfo) {
+        self.models.insert(model.into(), info);
+    }
+    
+    /// Look up a model
+    pub fn get(&self, model: &str) -> Option<&ModelInfo> {
+        lookup(&self.models, model)
+    }
+    
+    /// Iterate over the catalog entries
+    pub fn models(&self) -> impl Iterator<Item = (&str, &ModelInfo)> {
+        self.models.iter().map(|(name, info)| (name.as_str(), info))
+    }
+    
+    /// Build a price table from the models that have a price
+    pub fn price_table(&self) -> PriceTable {
+        let mut table = PriceTable::empty();
+        for (model, info) in &self.models {
+            if let Some(price) = info.price {
+                table.set(model.clone(), price);
+            }
+        }
+        table
+    }
+}
+
+/// Find the entry for a model by exact name or longest matching family
+pub(crate) fn lookup<'a, T>(entries: &'a HashMap<String, T>, model: &str) -> Option<&'a T> {
+    entries.get(model).or_else(|| {
+        entries.iter()
+            .filter(|(key, _)| {
+                model.strip_prefix(key.as_str()).is_some_and(|rest| rest.starts_with('-'))
+            })
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, entry)| entry)
+    })
+}
@@ -0,0 +1,85 @@
+use crate::components::llm_interface::{Message, Role};
+use serde::{Deserialize, Serialize};
+
+/// Ordered history of a multi-turn conversation
+/// 
+/// A conversation is sent to the LLM as a whole so the model sees
+/// every previous turn. `LLMInterface::chat` appends the assistant
+/// reply automatically.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Conversation {
+    messages: Vec<Message>,
+}
+
+impl Conversation {
+    /// Create an empty conversation
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Create a conversation that starts with a system prompt
+    pub fn with_system(prompt: impl Into<String>) -> Self {
+        let mut conversation = Self::new();
+        conversation.add_system(prompt);
+        conversation
+    }
+    
+    /// Append a message to the history
+    pub fn push(&mut self, message: Message) {
+        self.messages.push(message);
+    }
+    
+    /// Append a system message
+    pub fn add_system(&mut self, content: impl Into<String>) {
+        self.push(Message::system(content));
+    }
+    
+    /// Append a user message
+    pub fn add_user(&mut self, content: impl Into<String>) {
+        self.push(Message::user(content));
+    }
+    
+    /// Append an assistant message
+    pub fn add_assistant(&mut self, content: impl Into<String>) {
+        self.push(Message::assistant(content));
+    }
+    
+    /// Append the result of a tool call
+    pub fn add_tool_result(&mut self, tool_call_id: impl Into<String>, content: impl Into<String>) {
+        self.push(Message::tool(tool_call_id, content));
+    }
+    
+    /// Get all messages in order
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+    
+    /// Replace the whole history
+    pub(crate) fn replace_messages(&mut self, messages: Vec<Message>) {
+        self.messages = messages;
+    }
+    
+    /// Get the most recent assistant reply
+    pub fn last_reply(&self) -> Option<&str> {
+        self.messages
+            .iter()
+            .rev()
+            .find(|m| m.role == Role::Assistant)
+            .map(|m| m.content.as_str())
+    }
+    
+    /// Get the number of messages
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+    
+    /// Check if the conversation has no messages
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+    
+    /// Remove all turns while keeping system prompts
+    pub fn clear(&mut self) {
+        self.messages.retain(|m| m.role == Role::System);
+    }
+}
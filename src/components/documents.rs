@@ -0,0 +1,566 @@
+use crate::components::{LLMInterface, Metadata, Tokenizer};
+use crate::{Error, Result};
+use regex::{Captures, Regex};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Directories `load_dir` never descends into
+const IGNORED_DIRS: &[&str] = &["target", "node_modules", "__pycache__"];
+
+/// Largest file `load_dir` reads unless told otherwise
+const DEFAULT_MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Source code extensions and the language they hold
+const CODE_EXTENSIONS: &[(&str, &str)] = &[
+    ("c", "c"),
+    ("h", "c"),
+    ("cc", "cpp"),
+    ("cpp", "cpp"),
+    ("hpp", "cpp"),
+    ("cs", "csharp"),
+    ("go", "go"),
+    ("java", "java"),
+    ("js", "javascript"),
+    ("jsx", "javascript"),
+    ("kt", "kotlin"),
+    ("php", "php"),
+    ("py", "python"),
+    ("rb", "ruby"),
+    ("rs", "rust"),
+    ("scala", "scala"),
+    ("sh", "shell"),
+    ("sql", "sql"),
+    ("swift", "swift"),
+    ("toml", "toml"),
+    ("ts", "typescript"),
+    ("tsx", "typescript"),
+    ("yaml", "yaml"),
+    ("yml", "yaml"),
+];
+
+/// Format of a loaded file, which decides how it is split
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocumentKind {
+    Text,
+    Markdown,
+    /// HTML, converted to Markdown-like text when loaded
+    Html,
+    Code { language: String },
+}
+
+impl DocumentKind {
+    /// Guess the kind from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "txt" | "text" | "rst" => Some(DocumentKind::Text),
+            "md" | "markdown" => Some(DocumentKind::Markdown),
+            "html" | "htm" => Some(DocumentKind::Html),
+            other => CODE_EXTENSIONS.iter()
+                .find(|(code_extension, _)| *code_extension == other)
+                .map(|(_, language)| DocumentKind::Code { language: language.to_string() }),
+        }
+    }
+    
+    /// Get the short name stored in chunk metadata
+    pub fn name(&self) -> &str {
+        match self {
+            DocumentKind::Text => "text",
+            DocumentKind::Markdown => "markdown",
+            DocumentKind::Html => "html",
+            DocumentKind::Code { .. } => "code",
+        }
+    }
+}
+
+/// File loaded for chunking
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceDocument {
+    pub path: PathBuf,
+    pub kind: DocumentKind,
+    /// File contents; for HTML, the text extracted from the markup
+    pub text: String,
+}
+
+impl SourceDocument {
+    /// Create a document from text that was not read by a loader
+    pub fn new(path: impl Into<PathBuf>, kind: DocumentKind, text: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            kind,
+            text: text.into(),
+        }
+    }
+}
+
+/// Reads text, Markdown, HTML and source files for retrieval
+/// 
+/// Hidden files and directories, build output such as `target` and
+/// `node_modules`, unsupported extensions, files over the size limit and
+/// files that are not UTF-8 are skipped when loading a directory.
+#[derive(Debug, Clone)]
+pub struct DocumentLoader {
+    recursive: bool,
+    max_file_bytes: u64,
+}
+
+impl DocumentLoader {
+    /// Create a loader that walks subdirectories and reads files up to 1 MiB
+    pub fn new() -> Self {
+        Self {
+            recursive: true,
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+        }
+    }
+    
+    /// Set whether `load_dir` walks subdirectories
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+    
+    /// Set the largest file `load_dir` reads
+    pub fn max_file_bytes(mut self, bytes: u64) -> Self {
+        self.max_file_bytes = bytes;
+        self
+    }
+    
+    /// Load a single file
+    pub fn load_file(&self, path: impl AsRef<Path>) -> Result<SourceDocument> {
+        let path = path.as_ref();
+        let kind = DocumentKind::from_path(path)
+            .ok_or_else(|| Error::Document(format!("{}: unsupported file type", path.display())))?;
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::Document(format!("{}: {}", path.display(), e)))?;
+        
+        let text = match kind {
+            DocumentKind::Html => html_to_text(&contents),
+            _ => contents,
+        };
+        Ok(SourceDocument::new(path, kind, text))
+    }
+    
+    /// Load every supported file in a directory, in path order
+    pub fn load_dir(&self, dir: impl AsRef<Path>) -> Result<Vec<SourceDocument>> {
+        let dir = dir.as_ref();
+        let mut paths = Vec::new();
+        self.collect_files(dir, &mut paths)?;
+        
+        let mut documents = Vec::new();
+        for path in paths {
+            let size = std::fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+            if size > self.max_file_bytes {
+                warn!("Skipping {} ({} bytes is over the limit)", path.display(), size);
+                continue;
+            }
+            
+            match self.load_file(&path) {
+                Ok(document) => documents.push(document),
+                Err(e) => warn!("Skipping {}", e),
+            }
+        }
+        
+        info!("Loaded {} documents from {}", documents.len(), dir.display());
+        Ok(documents)
+    }
+    
+    /// Collect the supported files under a directory
+    fn collect_files(&self, dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| Error::Document(format!("{}: {}", dir.display(), e)))?;
+        let mut entries: Vec<_> = entries.filter_map(|entry| entry.ok()).collect();
+        entries.sort_by_key(|entry| entry.path());
+        
+        for entry in entries {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') {
+                continue;
+            }
+            
+            // Symlinks are neither files nor directories here, so they are not followed
+            let Ok(file_type) = entry.file_type() else { continue };
+            if file_type.is_dir() {
+                if self.recursive && !IGNORED_DIRS.contains(&name.as_ref()) {
+                    self.collect_files(&entry.path(), paths)?;
+                }
+            } else if file_type.is_file() && DocumentKind::from_path(&entry.path()).is_some() {
+                paths.push(entry.path());
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+impl Default for DocumentLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Convert HTML to text, keeping headings and preformatted blocks as Markdown
+fn html_to_text(html: &str) -> String {
+    let hidden = Regex::new(r"(?is)<script\b.*?</script\s*>|<style\b.*?</style\s*>|<head\b.*?</head\s*>|<!--.*?-->").unwrap();
+    let heading = Regex::new(r"(?is)<h([1-6])\b[^>]*>(.*?)</h[1-6]\s*>").unwrap();
+    let pre_open = Regex::new(r"(?i)<pre\b[^>]*>").unwrap();
+    let pre_close = Regex::new(r"(?i)</pre\s*>").unwrap();
+    let line_break = Regex::new(r"(?i)<br\s*/?>").unwrap();
+    let block = Regex::new(r"(?i)</?(p|div|li|ul|ol|tr|table|section|article|header|footer|nav|main|blockquote)\b[^>]*>").unwrap();
+    let tag = Regex::new(r"(?s)<[^>]*>").unwrap();
+    
+    let text = hidden.replace_all(html, "");
+    let text = heading.replace_all(&text, |caps: &Captures| {
+        let level: usize = caps[1].parse().unwrap_or(1);
+        let title = caps[2].split_whitespace().collect::<Vec<_>>().join(" ");
+        format!("\n\n{} {}\n\n", "#".repeat(level), title)
+    });
+    let text = pre_open.replace_all(&text, "\n\n```\n");
+    let text = pre_close.replace_all(&text, "\n```\n\n");
+    let text = line_break.replace_all(&text, "\n");
+    let text = block.replace_all(&text, "\n\n");
+    let text = tag.replace_all(&text, "");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    
+    // Collapse the source's indentation and blank lines, except inside code blocks
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_code = false;
+    for line in text.lines() {
+        if line.trim() == "```" {
+            in_code = !in_code;
+            lines.push("```".to_string());
+        } else if in_code {
+            lines.push(line.trim_end().to_string());
+        } else {
+            let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !line.is_empty() || lines.last().is_some_and(|last| !last.is_empty()) {
+                lines.push(line);
+            }
+        }
+    }
+    
+    lines.join("\n").trim().to_string()
+}
+
+/// Piece of a document sized to fit an embedding or prompt budget
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub text: String,
+    pub source: PathBuf,
+    pub kind: DocumentKind,
+    /// Position of the chunk within its document
+    pub index: usize,
+    /// Byte offset of the chunk in the document text
+    pub start: usize,
+    /// Byte offset just past the end of the chunk
+    pub end: usize,
+    pub tokens: usize,
+    /// Markdown heading path, such as `Install > Linux`
+    pub heading: Option<String>,
+}
+
+impl Chunk {
+    /// Build the metadata stored with the chunk in a vector store
+    pub fn metadata(&self) -> Metadata {
+        let mut metadata = Metadata::new();
+        metadata.insert("source".to_string(), self.source.display().to_string().into());
+        metadata.insert("kind".to_string(), self.kind.name().into());
+        if let DocumentKind::Code { language } = &self.kind {
+            metadata.insert("language".to_string(), language.as_str().into());
+        }
+        metadata.insert("chunk".to_string(), self.index.into());
+        metadata.insert("start".to_string(), self.start.into());
+        metadata.insert("end".to_string(), self.end.into());
+        metadata.insert("tokens".to_string(), self.tokens.into());
+        if let Some(heading) = &self.heading {
+            metadata.insert("heading".to_string(), heading.as_str().into());
+        }
+        metadata
+    }
+}
+
+/// Run of text the chunker keeps in one chunk when it can
+#[derive(Debug, Clone)]
+struct Segment {
+    start: usize,
+    end: usize,
+    /// Starts a Markdown section, so no chunk spans into it
+    starts_section: bool,
+    heading: Option<String>,
+}
+
+impl Segment {
+    /// Part of this segment that continues its section
+    fn piece(&self, start: usize, end: usize) -> Self {
+        Self {
+            start,
+            end,
+            starts_section: false,
+            heading: self.heading.clone(),
+        }
+    }
+}
+
+/// Splits documents into chunks by token count
+/// 
+/// Chunks are cut at paragraph boundaries, never inside a fenced code
+/// block that fits, and Markdown headings always start a new chunk.
+/// Paragraphs too long for one chunk are split by lines, then words.
+/// Consecutive chunks of a section share up to `overlap_tokens` tokens
+/// of text. Tokens are counted with the tokenizer of the target model,
+/// so chunk sizes match what `LLMInterface` counts.
+#[derive(Debug, Clone, Copy)]
+pub struct Chunker {
+    tokenizer: Tokenizer,
+    max_tokens: usize,
+    overlap_tokens: usize,
+}
+
+impl Chunker {
+    /// Create a chunker; the overlap is capped at half the chunk size
+    pub fn new(tokenizer: Tokenizer, max_tokens: usize, overlap_tokens: usize) -> Self {
+        let max_tokens = max_tokens.max(1);
+        if overlap_tokens > max_tokens / 2 {
+            warn!("Overlap of {} tokens capped at {}", overlap_tokens, max_tokens / 2);
+        }
+        
+        Self {
+            tokenizer,
+            max_tokens,
+            overlap_tokens: overlap_tokens.min(max_tokens / 2),
+        }
+    }
+    
+    /// Create a chunker counting tokens like an interface's model does
+    pub fn for_interface(llm: &LLMInterface, max_tokens: usize, overlap_tokens: usize) -> Self {
+        Self::new(*llm.tokenizer(), max_tokens, overlap_tokens)
+    }
+    
+    /// Get the largest chunk size in tokens
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+    
+    /// Get the tokens shared by consecutive chunks
+    pub fn overlap_tokens(&self) -> usize {
+        self.overlap_tokens
+    }
+    
+    /// Split several documents, keeping document order
+    pub fn chunk_all(&self, documents: &[SourceDocument]) -> Vec<Chunk> {
+        documents.iter().flat_map(|document| self.chunk(document)).collect()
+    }
+    
+    /// Split a document into chunks
+    pub fn chunk(&self, document: &SourceDocument) -> Vec<Chunk> {
+        let text = document.text.as_str();
+        let mut units = Vec::new();
+        for segment in segments(text, &document.kind) {
+            if self.count(text, segment.start, segment.end) <= self.max_tokens {
+                units.push(segment);
+            } else {
+                self.split_oversized(text, segment, &mut units);
+            }
+        }
+        
+        let mut chunks = Vec::new();
+        let mut emit = |start: usize, end: usize, heading: Option<String>| {
+            chunks.push(Chunk {
+                text: text[start..end].to_string(),
+                source: document.path.clone(),
+                kind: document.kind.clone(),
+                index: chunks.len(),
+                start,
+                end,
+                tokens: self.count(text, start, end),
+                heading,
+            });
+        };
+        
+        // Greedy packing; `tokens` is the running count of the open chunk
+        let mut current: Option<(usize, usize, Option<String>)> = None;
+        let mut tokens = 0;
+        for unit in units {
+            if let Some((start, end, heading)) = current.take() {
+                let added = self.count(text, end, unit.end);
+                if !unit.starts_section && tokens + added <= self.max_tokens {
+                    current = Some((start, unit.end, heading));
+                    tokens += added;
+                    continue;
+                }
+                
+                emit(start, end, heading);
+                if !unit.starts_section {
+                    let overlap = self.overlap_start(text, start, end)
+                        .map(|overlap| (overlap, self.count(text, overlap, unit.end)))
+                        .filter(|(_, overlap_tokens)| *overlap_tokens <= self.max_tokens);
+                    if let Some((overlap, overlap_tokens)) = overlap {
+                        current = Some((overlap, unit.end, unit.heading));
+                        tokens = overlap_tokens;
+                        continue;
+                    }
+                }
+            }
+            
+            tokens = self.count(text, unit.start, unit.end);
+            current = Some((unit.start, unit.end, unit.heading));
+        }
+        if let Some((start, end, heading)) = current {
+            emit(start, end, heading);
+        }
+        
+        chunks
+    }
+    
+    fn count(&self, text: &str, start: usize, end: usize) -> usize {
+        self.tokenizer.count_tokens(&text[start..end])
+    }
+    
+    /// Earliest word boundary in a chunk whose tail fits the overlap
+    fn overlap_start(&self, text: &str, start: usize, end: usize) -> Option<usize> {
+        if self.overlap_tokens == 0 {
+            return None;
+        }
+        
+        let boundaries: Vec<usize> = word_ranges(text, start, end).map(|(word_start, _)| word_start).collect();
+        boundaries.into_iter()
+            .filter(|boundary| *boundary > start)
+            .rev()
+            .take_while(|boundary| self.count(text, *boundary, end) <= self.overlap_tokens)
+            .last()
+    }
+    
+    /// Break a segment over the chunk size into lines, words or characters that fit
+    fn split_oversized(&self, text: &str, segment: Segment, units: &mut Vec<Segment>) {
+        let first = units.len();
+        for (line_start, line_end) in line_ranges(text, segment.start, segment.end) {
+            if self.count(text, line_start, line_end) <= self.max_tokens {
+                units.push(segment.piece(line_start, line_end));
+                continue;
+            }
+            
+            for (word_start, word_end) in word_ranges(text, line_start, line_end) {
+                let mut start = word_start;
+                while start < word_end {
+                    let end = self.longest_fit(text, start, word_end);
+                    units.push(segment.piece(start, end));
+                    start = end;
+                }
+            }
+        }
+        
+        if let Some(unit) = units.get_mut(first) {
+            unit.starts_section = segment.starts_section;
+        }
+    }
+    
+    /// Furthest character boundary up to `end` whose text from `start` fits, by bisection
+    fn longest_fit(&self, text: &str, start: usize, end: usize) -> usize {
+        let boundaries: Vec<usize> = text[start..end].char_indices()
+            .map(|(offset, c)| start + offset + c.len_utf8())
+            .collect();
+        let fitting = boundaries.partition_point(|boundary| self.count(text, start, *boundary) <= self.max_tokens);
+        
+        // Always take at least one character so splitting makes progress
+        boundaries[fitting.saturating_sub(1)]
+    }
+}
+
+/// Byte ranges of the non-empty lines in `text[start..end]`, without line endings
+fn line_ranges(text: &str, start: usize, end: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut offset = start;
+    text[start..end].split_inclusive('\n').filter_map(move |line| {
+        let line_start = offset;
+        offset += line.len();
+        let content = line.trim_end();
+        (!content.is_empty()).then_some((line_start, line_start + content.len()))
+    })
+}
+
+/// Byte ranges of the whitespace-separated words in `text[start..end]`
+fn word_ranges(text: &str, start: usize, end: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let slice = &text[start..end];
+    slice.split_whitespace().map(move |word| {
+        let word_start = start + (word.as_ptr() as usize - slice.as_ptr() as usize);
+        (word_start, word_start + word.len())
+    })
+}
+
+/// Split a document into paragraphs, fenced code blocks and Markdown headings
+fn segments(text: &str, kind: &DocumentKind) -> Vec<Segment> {
+    let markdown = matches!(kind, DocumentKind::Markdown | DocumentKind::Html);
+    let mut segments = Vec::new();
+    let mut headings: Vec<(usize, String)> = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+    let mut fence: Option<&str> = None;
+    
+    let heading_path = |headings: &[(usize, String)]| {
+        (!headings.is_empty()).then(|| {
+            headings.iter().map(|(_, title)| title.as_str()).collect::<Vec<_>>().join(" > ")
+        })
+    };
+    let flush = |current: &mut Option<(usize, usize)>, segments: &mut Vec<Segment>, heading: Option<String>| {
+        if let Some((start, end)) = current.take() {
+            segments.push(Segment { start, end, starts_section: false, heading });
+        }
+    };
+    
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let end = start + line.trim_end().len();
+        let trimmed = line.trim();
+        
+        if markdown {
+            // Everything up to the closing fence stays together
+            if let Some(marker) = fence {
+                current = current.map(|(block_start, _)| (block_start, end));
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                    flush(&mut current, &mut segments, heading_path(&headings));
+                }
+                continue;
+            }
+            
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                flush(&mut current, &mut segments, heading_path(&headings));
+                fence = Some(&trimmed[..3]);
+                current = Some((start, end));
+                continue;
+            }
+            
+            let level = trimmed.chars().take_while(|c| *c == '#').count();
+            let is_heading = (1..=6).contains(&level)
+                && trimmed[level..].chars().next().is_none_or(char::is_whitespace);
+            if is_heading {
+                flush(&mut current, &mut segments, heading_path(&headings));
+                headings.retain(|(heading_level, _)| *heading_level < level);
+                headings.push((level, trimmed[level..].trim().to_string()));
+                segments.push(Segment {
+                    start,
+                    end,
+                    starts_section: true,
+                    heading: heading_path(&headings),
+                });
+                continue;
+            }
+        }
+        
+        if trimmed.is_empty() {
+            flush(&mut current, &mut segments, heading_path(&headings));
+        } else {
+            current = Some(current.map_or((start, end), |(block_start, _)| (block_start, end)));
+        }
+    }
+    flush(&mut current, &mut segments, heading_path(&headings));
+    
+    segments
+}
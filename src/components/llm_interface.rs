@@ -1,8 +1,34 @@
-use crate::components::{AIComponent, NamedComponent};
-use anyhow::Result;
+use crate::components::cache::ResponseCache;
+use crate::components::cassette::{Cassette, CassetteMode};
+use crate::components::catalog::{Feature, ModelCatalog, ModelInfo};
+use crate::components::pricing::{Budget, ModelPrice, PriceTable};
+use crate::components::providers::{AzureOptions, LLMProvider, OllamaOptions, ProviderKind};
+use crate::components::rate_limiter::{estimate_request_tokens, RateLimiter};
+use crate::components::retry::RetryPolicy;
+use crate::components::tokenizer::Tokenizer;
+use crate::components::truncation::{
+    drop_oldest_turns, keep_recent_turns, older_turns_transcript, replace_older_turns,
+    TruncationStrategy, SUMMARY_INSTRUCTIONS,
+};
+use crate::components::{AIComponent, Conversation, NamedComponent, ToolRegistry};
+use crate::{Error, Result};
+use futures::stream::{self, StreamExt};
+use futures::Stream;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
 use tracing::{info, warn};
 
+/// Upper bound on model/tool round trips in a single `chat_with_tools` call
+const MAX_TOOL_ROUNDS: usize = 10;
+
+/// Attempts at getting schema-conforming JSON before `chat_structured` gives up
+const MAX_STRUCTURED_ATTEMPTS: usize = 3;
+
 /// Configuration for LLM API requests
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LLMConfig {
@@ -10,35 +36,618 @@ pub struct LLMConfig {
     pub max_tokens: Option<u32>,
     pub temperature: Option<f32>,
     pub api_key: Option<String>,
+    #[serde(default)]
+    pub provider: ProviderKind,
+    /// API root such as `http://localhost:8000/v1`; the provider's public API when unset
+    pub base_url: Option<String>,
+    /// Extra headers sent with every request
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// OpenAI organization ID
+    pub organization: Option<String>,
+    /// OpenAI project ID
+    pub project: Option<String>,
+    /// Azure OpenAI deployment settings
+    #[serde(default)]
+    pub azure: AzureOptions,
+    /// Ollama model options
+    #[serde(default)]
+    pub ollama: OllamaOptions,
+    /// Model used by `embed`; the provider's default embedding model when unset
+    pub embedding_model: Option<String>,
+    /// Length to shorten embeddings to, for models that support it
+    pub embedding_dimensions: Option<u32>,
+    /// How transient failures are retried
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// How conversations are shortened to fit the context window
+    #[serde(default)]
+    pub truncation: TruncationStrategy,
+    /// Limiter consulted before each call, possibly shared with other interfaces
+    #[serde(skip)]
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Spending cap checked before each call, possibly shared with other interfaces
+    #[serde(skip)]
+    pub budget: Option<Arc<Budget>>,
+    /// Records or replays the HTTP traffic of every call
+    #[serde(skip)]
+    pub cassette: Option<Arc<Cassette>>,
+    /// Serves repeated deterministic requests without calling the API
+    #[serde(skip)]
+    pub cache: Option<Arc<ResponseCache>>,
+}
+
+impl LLMConfig {
+    /// Create a config for the given model with default settings
+    pub fn new(model: String) -> Self {
+        let provider = ProviderKind::from_model(&model);
+        
+        Self {
+            model,
+            max_tokens: Some(1000),
+            temperature: Some(0.7),
+            api_key: None,
+            provider,
+            base_url: None,
+            headers: HashMap::new(),
+            organization: None,
+            project: None,
+            azure: AzureOptions::default(),
+            ollama: OllamaOptions::default(),
+            embedding_model: None,
+            embedding_dimensions: None,
+            retry: RetryPolicy::default(),
+            truncation: TruncationStrategy::default(),
+            rate_limiter: None,
+            budget: None,
+            cassette: None,
+            cache: None,
+        }
+    }
+    
+    /// Create a config for an Azure OpenAI deployment of `model`
+    /// 
+    /// `endpoint` is the resource URL, such as `https://my-resource.openai.azure.com`.
+    pub fn azure(endpoint: impl Into<String>, deployment: impl Into<String>, model: impl Into<String>) -> Self {
+        let mut config = Self::new(model.into());
+        config.provider = ProviderKind::Azure;
+        config.base_url = Some(endpoint.into());
+        config.azure.deployment = Some(deployment.into());
+        config
+    }
+}
+
+impl Default for LLMConfig {
+    fn default() -> Self {
+        Self::new("gpt-3.5-turbo".to_string())
+    }
+}
+
+/// Stream of text deltas produced while a response is generated
+/// 
+/// Providers that report token usage while streaming fill in `usage`
+/// by the time the stream ends.
+pub struct TokenStream {
+    deltas: Pin<Box<dyn Stream<Item = Result<String>> + Send>>,
+    usage: Arc<Mutex<Option<Usage>>>,
+}
+
+impl TokenStream {
+    /// Wrap a stream of text deltas that reports no usage
+    pub fn new(deltas: impl Stream<Item = Result<String>> + Send + 'static) -> Self {
+        Self::with_usage(deltas, Arc::default())
+    }
+    
+    /// Wrap a stream of text deltas whose producer records usage in `usage`
+    pub(crate) fn with_usage(
+        deltas: impl Stream<Item = Result<String>> + Send + 'static,
+        usage: Arc<Mutex<Option<Usage>>>,
+    ) -> Self {
+        Self {
+            deltas: Box::pin(deltas),
+            usage,
+        }
+    }
+    
+    /// Get the token usage reported by the provider so far
+    pub fn usage(&self) -> Option<Usage> {
+        *self.usage.lock().unwrap()
+    }
+}
+
+impl Stream for TokenStream {
+    type Item = Result<String>;
+    
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.deltas.as_mut().poll_next(cx)
+    }
+}
+
+impl std::fmt::Debug for TokenStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenStream").field("usage", &self.usage()).finish_non_exhaustive()
+    }
+}
+
+/// Provider-neutral request handed to a backend
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChatRequest<'a> {
+    pub messages: &'a [Message],
+    pub tools: &'a [Tool],
+    /// Schema the reply content must conform to
+    pub response_schema: Option<&'a ResponseSchema>,
+    /// Model takes `max_completion_tokens` and no temperature; set from
+    /// the catalog before the request reaches the backend
+    pub reasoning: bool,
+}
+
+impl<'a> ChatRequest<'a> {
+    /// Create a request for the given messages
+    pub fn new(messages: &'a [Message]) -> Self {
+        Self {
+            messages,
+            ..Default::default()
+        }
+    }
+}
+
+/// Provider-neutral embedding request handed to a backend
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddingRequest<'a> {
+    /// Texts to embed, no more than the provider accepts at once
+    pub inputs: &'a [String],
+    pub model: &'a str,
+    /// Length to shorten the vectors to, for models that support it
+    pub dimensions: Option<u32>,
+}
+
+/// Vectors returned for an embedding request, in input order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Embeddings {
+    pub vectors: Vec<Vec<f32>>,
+    pub usage: Option<Usage>,
 }
 
 /// Request structure for LLM API calls
 #[derive(Debug, Serialize)]
-struct LLMRequest {
-    model: String,
-    messages: Vec<Message>,
-    max_tokens: Option<u32>,
-    temperature: Option<f32>,
+pub(crate) struct LLMRequest<'a> {
+    pub(crate) model: &'a str,
+    pub(crate) messages: Vec<ChatMessage<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) max_tokens: Option<u32>,
+    /// Output limit of reasoning models, which reject `max_tokens`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) max_completion_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) stream_options: Option<StreamOptions>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) tools: Vec<FunctionTool<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) response_format: Option<ResponseFormat<'a>>,
+}
+
+/// Streaming settings in the OpenAI format
+#[derive(Debug, Serialize)]
+pub(crate) struct StreamOptions {
+    /// Ask for a final chunk carrying the token usage
+    pub(crate) include_usage: bool,
+}
+
+/// Structured output setting in the OpenAI `response_format` format
+#[derive(Debug, Serialize)]
+pub(crate) struct ResponseFormat<'a> {
+    #[serde(rename = "type")]
+    pub(crate) kind: &'static str,
+    pub(crate) json_schema: &'a ResponseSchema,
+}
+
+impl<'a> From<&'a ResponseSchema> for ResponseFormat<'a> {
+    fn from(schema: &'a ResponseSchema) -> Self {
+        Self {
+            kind: "json_schema",
+            json_schema: schema,
+        }
+    }
+}
+
+/// Message in the OpenAI chat format
+#[derive(Debug, Serialize)]
+pub(crate) struct ChatMessage<'a> {
+    pub(crate) role: Role,
+    pub(crate) content: ChatContent<'a>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub(crate) tool_calls: &'a [ToolCall],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) tool_call_id: Option<&'a str>,
+}
+
+/// Message content, plain text unless images are attached
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub(crate) enum ChatContent<'a> {
+    Text(&'a str),
+    Parts(Vec<ContentPart<'a>>),
+}
+
+/// A single part of multimodal content
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ContentPart<'a> {
+    Text { text: &'a str },
+    ImageUrl { image_url: &'a Image },
+}
+
+impl<'a> From<&'a Message> for ChatMessage<'a> {
+    fn from(message: &'a Message) -> Self {
+        let content = if message.images.is_empty() {
+            ChatContent::Text(&message.content)
+        } else {
+            let images = message.images.iter().map(|image| ContentPart::ImageUrl { image_url: image });
+            ChatContent::Parts(std::iter::once(ContentPart::Text { text: &message.content }).chain(images).collect())
+        };
+        Self {
+            role: message.role,
+            content,
+            tool_calls: &message.tool_calls,
+            tool_call_id: message.tool_call_id.as_deref(),
+        }
+    }
+}
+
+/// Tool definition in the OpenAI `tools` format
+#[derive(Debug, Serialize)]
+pub(crate) struct FunctionTool<'a> {
+    #[serde(rename = "type")]
+    pub(crate) kind: &'static str,
+    pub(crate) function: &'a Tool,
+}
+
+impl<'a> From<&'a Tool> for FunctionTool<'a> {
+    fn from(tool: &'a Tool) -> Self {
+        Self {
+            kind: "function",
+            function: tool,
+        }
+    }
+}
+
+/// Author of a message in a conversation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+    Tool,
 }
 
 /// Message structure for LLM conversations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    pub role: Role,
+    #[serde(deserialize_with = "null_as_empty")]
+    pub content: String,
+    /// Tools the assistant asked to call
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    /// Call this tool result answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// Images attached to a user message
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<Image>,
+}
+
+impl Message {
+    /// Create a message with the given role
+    pub fn new(role: Role, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+            images: Vec::new(),
+        }
+    }
+    
+    /// Create a system message
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new(Role::System, content)
+    }
+    
+    /// Create a user message
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new(Role::User, content)
+    }
+    
+    /// Create an assistant message
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new(Role::Assistant, content)
+    }
+    
+    /// Create a tool result message answering the given call
+    pub fn tool(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            tool_call_id: Some(tool_call_id.into()),
+            ..Self::new(Role::Tool, content)
+        }
+    }
+    
+    /// Attach an image; the model must support `Feature::Vision`
+    pub fn with_image(mut self, image: Image) -> Self {
+        self.images.push(image);
+        self
+    }
+}
+
+/// Image input, given by URL or inline as a `data:` URL
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Image {
+    pub url: String,
+}
+
+impl Image {
+    /// Refer to an image by URL
+    pub fn url(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+    
+    /// Inline base64-encoded image data of a media type such as `image/png`
+    pub fn base64(media_type: &str, data: &str) -> Self {
+        Self::url(format!("data:{};base64,{}", media_type, data))
+    }
+    
+    /// Get the media type and base64 data of an inline image
+    pub(crate) fn inline_data(&self) -> Option<(&str, &str)> {
+        self.url.strip_prefix("data:")?.split_once(";base64,")
+    }
+}
+
+/// Assistant messages that only call tools have `null` content
+fn null_as_empty<'de, D>(deserializer: D) -> std::result::Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Tool the model may call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tool {
+    pub name: String,
+    pub description: String,
+    /// JSON Schema describing the arguments
+    pub parameters: serde_json::Value,
+}
+
+impl Tool {
+    /// Create a tool definition
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        parameters: serde_json::Value,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            parameters,
+        }
+    }
+}
+
+/// Tool invocation requested by the model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "FunctionCall", into = "FunctionCall")]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub arguments: serde_json::Value,
+}
+
+/// Tool call in the OpenAI wire format, with arguments as a JSON string
+#[derive(Debug, Serialize, Deserialize)]
+struct FunctionCall {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    function: FunctionCallBody,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
-struct Message {
-    role: String,
-    content: String,
+struct FunctionCallBody {
+    name: String,
+    arguments: String,
+}
+
+impl From<FunctionCall> for ToolCall {
+    fn from(call: FunctionCall) -> Self {
+        // Models occasionally emit invalid JSON; keep the raw text so handlers can see it
+        let arguments = serde_json::from_str(&call.function.arguments)
+            .unwrap_or(serde_json::Value::String(call.function.arguments));
+        
+        Self {
+            id: call.id,
+            name: call.function.name,
+            arguments,
+        }
+    }
+}
+
+impl From<ToolCall> for FunctionCall {
+    fn from(call: ToolCall) -> Self {
+        Self {
+            id: call.id,
+            kind: "function".to_string(),
+            function: FunctionCallBody {
+                name: call.name,
+                arguments: call.arguments.to_string(),
+            },
+        }
+    }
+}
+
+/// Named JSON Schema for structured output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseSchema {
+    pub name: String,
+    pub schema: serde_json::Value,
+}
+
+impl ResponseSchema {
+    /// Generate the schema for a Rust type
+    pub fn for_type<T: JsonSchema>() -> Self {
+        let mut schema = serde_json::to_value(schemars::schema_for!(T))
+            .unwrap_or_default();
+        if let Some(object) = schema.as_object_mut() {
+            object.remove("$schema");
+        }
+        
+        // Schema names may only contain letters, digits, underscores and dashes
+        let name: String = T::schema_name()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+            .collect();
+        
+        Self { name, schema }
+    }
+}
+
+/// Why the model stopped generating
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinishReason {
+    /// Natural end of the reply or a stop sequence
+    Stop,
+    /// The output hit `max_tokens` and is truncated
+    Length,
+    /// The model wants to call tools
+    ToolCalls,
+    /// Content was withheld by a safety filter
+    ContentFilter,
+    /// Provider-specific reason not covered above
+    Other(String),
+}
+
+impl FinishReason {
+    /// Normalize a provider's finish or stop reason
+    pub fn from_provider(reason: &str) -> Self {
+        match reason {
+            "stop" | "end_turn" | "stop_sequence" => FinishReason::Stop,
+            "length" | "max_tokens" => FinishReason::Length,
+            "tool_calls" | "function_call" | "tool_use" => FinishReason::ToolCalls,
+            "content_filter" | "refusal" | "safety" | "recitation" | "prohibited_content" => {
+                FinishReason::ContentFilter
+            }
+            other => FinishReason::Other(other.to_string()),
+        }
+    }
+}
+
+/// Token counts reported for a call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    /// Prompt tokens, including any served from the prompt cache
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+    /// Prompt tokens served from the provider's prompt cache
+    pub cached_tokens: u32,
+}
+
+impl Usage {
+    /// Combine counts reported in pieces while a reply is streamed
+    /// 
+    /// Providers repeat or grow their counts as the stream goes on, so
+    /// the larger value of each count wins.
+    pub(crate) fn merge(&mut self, other: Usage) {
+        self.prompt_tokens = self.prompt_tokens.max(other.prompt_tokens);
+        self.completion_tokens = self.completion_tokens.max(other.completion_tokens);
+        self.cached_tokens = self.cached_tokens.max(other.cached_tokens);
+        self.total_tokens = (self.prompt_tokens + self.completion_tokens).max(other.total_tokens);
+    }
+}
+
+/// Outcome of a response cache lookup
+enum CacheLookup {
+    /// Reply served from the cache
+    Hit(Completion),
+    /// Not cached yet; the reply is stored under this key
+    Miss(String),
+    /// The cache does not apply to this request
+    Skip,
+}
+
+/// Full result of a call: the reply plus its metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Completion {
+    /// Response ID assigned by the provider
+    pub id: Option<String>,
+    /// Model that actually served the request
+    pub model: Option<String>,
+    pub message: Message,
+    pub finish_reason: Option<FinishReason>,
+    pub usage: Option<Usage>,
+    /// Dollar cost of the call, when the model has a known price
+    #[serde(default)]
+    pub cost: Option<f64>,
+}
+
+impl Completion {
+    /// Get the reply text
+    pub fn text(&self) -> &str {
+        &self.message.content
+    }
+    
+    /// Check if the reply was cut off by the output token limit
+    pub fn is_truncated(&self) -> bool {
+        self.finish_reason == Some(FinishReason::Length)
+    }
 }
 
 /// Response structure from LLM API
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct LLMResponse {
-    choices: Vec<Choice>,
+pub(crate) struct LLMResponse {
+    pub(crate) id: Option<String>,
+    pub(crate) model: Option<String>,
+    pub(crate) choices: Vec<Choice>,
+    pub(crate) usage: Option<LLMUsage>,
 }
 
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct Choice {
-    message: Message,
+pub(crate) struct Choice {
+    pub(crate) message: Message,
+    pub(crate) finish_reason: Option<String>,
+}
+
+/// Usage block in the OpenAI format
+#[derive(Debug, Deserialize)]
+pub(crate) struct LLMUsage {
+    pub(crate) prompt_tokens: u32,
+    pub(crate) completion_tokens: u32,
+    pub(crate) total_tokens: u32,
+    pub(crate) prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct PromptTokensDetails {
+    pub(crate) cached_tokens: Option<u32>,
+}
+
+impl From<LLMUsage> for Usage {
+    fn from(usage: LLMUsage) -> Self {
+        Self {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            total_tokens: usage.total_tokens,
+            cached_tokens: usage.prompt_tokens_details
+                .and_then(|details| details.cached_tokens)
+                .unwrap_or(0),
+        }
+    }
 }
 
 /// Interface for Large Language Model interactions
@@ -50,63 +659,659 @@ pub struct LLMInterface {
     name: String,
     description: String,
     config: LLMConfig,
+    provider: Box<dyn LLMProvider>,
     client: Option<reqwest::Client>,
     is_connected: bool,
+    catalog: ModelCatalog,
+    prices: PriceTable,
+    total_cost: Arc<Mutex<f64>>,
+    tokenizer: Tokenizer,
+    summarizer: Option<Box<LLMInterface>>,
 }
 
 impl LLMInterface {
     /// Create a new LLM Interface
     pub fn new(model: Option<String>) -> Self {
-        let model = model.unwrap_or_else(|| "gpt-3.5-turbo".to_string());
-        
+        match model {
+            Some(model) => Self::with_config(LLMConfig::new(model)),
+            None => Self::with_config(LLMConfig::default()),
+        }
+    }
+    
+    /// Create a new LLM Interface from a full configuration
+    pub fn with_config(config: LLMConfig) -> Self {
         Self {
             name: "LLM Interface".to_string(),
             description: "Interface for Large Language Models".to_string(),
-            config: LLMConfig {
-                model,
-                max_tokens: Some(1000),
-                temperature: Some(0.7),
-                api_key: None,
-            },
+            provider: config.provider.create(),
+            tokenizer: Tokenizer::for_model(&config.model),
+            config,
             client: None,
             is_connected: false,
+            catalog: ModelCatalog::built_in().clone(),
+            prices: PriceTable::default(),
+            total_cost: Arc::default(),
+            summarizer: None,
         }
     }
     
+    /// Create a new LLM Interface that talks to a custom provider backend
+    /// 
+    /// Use this to plug in a `MockProvider` for tests or a backend
+    /// implemented outside this crate.
+    pub fn with_provider(config: LLMConfig, provider: impl LLMProvider + 'static) -> Self {
+        let mut llm = Self::with_config(config);
+        llm.provider = Box::new(provider);
+        info!("Provider set to: {}", llm.provider.name());
+        llm
+    }
+    
     /// Set the API key for authentication
     pub fn set_api_key(&mut self, key: String) {
         self.config.api_key = Some(key);
         info!("API key set for model: {}", self.config.model);
     }
     
+    /// Select the provider backend
+    /// 
+    /// By default the provider is guessed from the model name.
+    pub fn set_provider(&mut self, provider: ProviderKind) {
+        self.config.provider = provider;
+        self.provider = provider.create();
+        info!("Provider set to: {}", self.provider.name());
+    }
+    
+    /// Set the API base URL
+    /// 
+    /// Points the interface at an OpenAI-compatible server such as vLLM,
+    /// llama.cpp, LM Studio, Ollama or an internal gateway.
+    pub fn set_base_url(&mut self, url: String) {
+        info!("Base URL set to: {}", url);
+        self.config.base_url = Some(url);
+    }
+    
+    /// Set the rate limiter consulted before each call
+    /// 
+    /// Pass clones of the same `Arc` to several interfaces to share a quota.
+    pub fn set_rate_limiter(&mut self, limiter: Arc<RateLimiter>) {
+        self.config.rate_limiter = Some(limiter);
+        info!("Rate limiter set for model: {}", self.config.model);
+    }
+    
+    /// Set the budget checked before each call
+    /// 
+    /// Pass clones of the same `Arc` to several interfaces to cap their
+    /// combined spend.
+    pub fn set_budget(&mut self, budget: Arc<Budget>) {
+        self.config.budget = Some(budget);
+        info!("Budget set for model: {}", self.config.model);
+    }
+    
+    /// Record or replay the HTTP traffic of every call
+    /// 
+    /// Replaying needs no API key, so tests can run against recorded
+    /// provider responses with no network.
+    pub fn set_cassette(&mut self, cassette: Arc<Cassette>) {
+        info!("Cassette set to {:?} {}", cassette.mode(), cassette.path().display());
+        self.config.cassette = Some(cassette);
+    }
+    
+    /// Set the response cache, possibly shared with other interfaces
+    pub fn set_cache(&mut self, cache: Arc<ResponseCache>) {
+        info!("Response cache set ({} entries)", cache.len());
+        self.config.cache = Some(cache);
+    }
+    
+    /// Set the model used for embeddings and the length to shorten them to
+    pub fn set_embedding_model(&mut self, model: String, dimensions: Option<u32>) {
+        info!("Embedding model set to: {} ({:?} dimensions)", model, dimensions);
+        self.config.embedding_model = Some(model);
+        self.config.embedding_dimensions = dimensions;
+    }
+    
+    /// Set how conversations are shortened to fit the context window
+    pub fn set_truncation(&mut self, strategy: TruncationStrategy) {
+        info!("Truncation strategy set to: {:?}", strategy);
+        self.config.truncation = strategy;
+    }
+    
+    /// Set the interface that writes summaries for `TruncationStrategy::Summarize`
+    /// 
+    /// A smaller, cheaper model is usually enough. Without one the
+    /// interface summarizes with its own model.
+    pub fn set_summarizer(&mut self, summarizer: LLMInterface) {
+        self.summarizer = Some(Box::new(summarizer));
+    }
+    
+    /// Replace the model catalog used to validate the model
+    /// 
+    /// The price table is rebuilt from the catalog, replacing earlier
+    /// price overrides.
+    pub fn set_catalog(&mut self, catalog: ModelCatalog) {
+        self.prices = catalog.price_table();
+        self.catalog = catalog;
+    }
+    
+    /// Replace the price table used for cost accounting
+    pub fn set_price_table(&mut self, prices: PriceTable) {
+        self.prices = prices;
+    }
+    
+    /// Add or override the price of a single model
+    pub fn set_price(&mut self, model: impl Into<String>, price: ModelPrice) {
+        self.prices.set(model, price);
+    }
+    
     /// Generate a response from the LLM
     pub async fn generate_response(&self, prompt: &str) -> Result<String> {
-        if !self.is_connected {
-            return Err(anyhow::anyhow!("LLM Interface not initialized"));
+        Ok(self.generate(prompt).await?.message.content)
+    }
+    
+    /// Generate a completion with usage and finish-reason metadata
+    pub async fn generate(&self, prompt: &str) -> Result<Completion> {
+        let messages = [Message::user(prompt)];
+        
+        info!("Generating response via {} for prompt: {}", self.provider.name(), prompt);
+        
+        self.send(&ChatRequest::new(&messages)).await
+    }
+    
+    /// Send a whole conversation and append the assistant reply to it
+    pub async fn chat(&self, conversation: &mut Conversation) -> Result<String> {
+        Ok(self.complete(conversation).await?.message.content)
+    }
+    
+    /// Send a whole conversation, append the reply and return the full completion
+    pub async fn complete(&self, conversation: &mut Conversation) -> Result<Completion> {
+        info!(
+            "Sending conversation of {} messages via {}",
+            conversation.len(),
+            self.provider.name()
+        );
+        
+        self.fit_conversation(conversation).await?;
+        let completion = self.send(&ChatRequest::new(conversation.messages())).await?;
+        conversation.push(completion.message.clone());
+        Ok(completion)
+    }
+    
+    /// Send a conversation with tools and run the calls the model makes
+    /// 
+    /// Tool calls are dispatched to the registry's handlers and their
+    /// results fed back until the model produces a final answer. Handler
+    /// errors are reported to the model so it can recover.
+    pub async fn chat_with_tools(
+        &self,
+        conversation: &mut Conversation,
+        tools: &ToolRegistry,
+    ) -> Result<String> {
+        for round in 1..=MAX_TOOL_ROUNDS {
+            info!(
+                "Sending conversation of {} messages with {} tools via {} (round {})",
+                conversation.len(),
+                tools.definitions().len(),
+                self.provider.name(),
+                round
+            );
+            
+            self.fit_conversation(conversation).await?;
+            let request = ChatRequest {
+                messages: conversation.messages(),
+                tools: tools.definitions(),
+                ..Default::default()
+            };
+            let reply = self.send(&request).await?.message;
+            let calls = reply.tool_calls.clone();
+            let content = reply.content.clone();
+            conversation.push(reply);
+            
+            if calls.is_empty() {
+                return Ok(content);
+            }
+            
+            for call in &calls {
+                info!("Calling tool {} with {}", call.name, call.arguments);
+                let result = tools.call(call).await.unwrap_or_else(|e| {
+                    warn!("Tool {} failed: {}", call.name, e);
+                    format!("Error: {}", e)
+                });
+                conversation.push(Message::tool(call.id.clone(), result));
+            }
         }
         
-        if self.config.api_key.is_none() {
-            return Err(anyhow::anyhow!("API key not set"));
+        Err(Error::ToolRoundsExceeded(MAX_TOOL_ROUNDS))
+    }
+    
+    /// Generate a response decoded into a Rust type
+    /// 
+    /// See `chat_structured` for how the output is requested and validated.
+    pub async fn generate_structured<T>(&self, prompt: &str) -> Result<T>
+    where
+        T: DeserializeOwned + JsonSchema,
+    {
+        let mut conversation = Conversation::new();
+        conversation.add_user(prompt);
+        self.chat_structured(&mut conversation).await
+    }
+    
+    /// Send a conversation and decode the reply into a Rust type
+    /// 
+    /// The JSON schema of `T` is sent with the request and the reply is
+    /// validated against it. Invalid replies are retried with the
+    /// validation error appended so the model can correct itself; each
+    /// retry resends the conversation as fitted for the first attempt.
+    pub async fn chat_structured<T>(&self, conversation: &mut Conversation) -> Result<T>
+    where
+        T: DeserializeOwned + JsonSchema,
+    {
+        let schema = ResponseSchema::for_type::<T>();
+        let validator = jsonschema::validator_for(&schema.schema)
+            .map_err(|e| Error::InvalidSchema {
+                name: schema.name.clone(),
+                message: e.to_string(),
+            })?;
+        let mut last_error = String::new();
+        
+        // Fit the request once; truncating again on a retry would count the
+        // correction as the latest turn and drop the original question
+        self.fit_conversation(conversation).await?;
+        let fitted = conversation.len();
+        
+        for attempt in 1..=MAX_STRUCTURED_ATTEMPTS {
+            info!(
+                "Requesting structured output {} via {} (attempt {})",
+                schema.name,
+                self.provider.name(),
+                attempt
+            );
+            
+            // Retries send only the latest rejected reply and its correction
+            let messages = conversation.messages();
+            let retry = &messages[fitted.max(messages.len().saturating_sub(2))..];
+            let messages: Vec<Message> = messages[..fitted].iter().chain(retry).cloned().collect();
+            let request = ChatRequest {
+                messages: &messages,
+                response_schema: Some(&schema),
+                ..Default::default()
+            };
+            let reply = self.send(&request).await?.message;
+            let content = reply.content.clone();
+            conversation.push(reply);
+            
+            match parse_structured(&content, &validator) {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    warn!("Structured output rejected: {}", e);
+                    conversation.add_user(format!(
+                        "Your reply did not match the required JSON schema: {}. \
+                         Reply again with only the corrected JSON.",
+                        e
+                    ));
+                    last_error = e;
+                }
+            }
         }
         
-        let _client = self.client.as_ref()
-            .ok_or_else(|| anyhow::anyhow!("HTTP client not initialized"))?;
+        Err(Error::StructuredOutput {
+            name: schema.name,
+            attempts: MAX_STRUCTURED_ATTEMPTS,
+            message: last_error,
+        })
+    }
+    
+    /// Stream a response from the LLM as it is generated
+    /// 
+    /// Each item of the returned stream is the next piece of the reply.
+    pub async fn stream_response(&self, prompt: &str) -> Result<TokenStream> {
+        let messages = [Message::user(prompt)];
+        
+        info!("Streaming response via {} for prompt: {}", self.provider.name(), prompt);
+        
+        self.require(Feature::Streaming)?;
+        let request = self.for_model(&ChatRequest::new(&messages));
+        self.ready_client()?;
+        self.check_features(&request)?;
+        let key = match self.cache_lookup(&request) {
+            CacheLookup::Hit(completion) => {
+                return Ok(TokenStream::new(stream::once(async move { Ok(completion.message.content) })));
+            }
+            CacheLookup::Miss(key) => Some(key),
+            CacheLookup::Skip => None,
+        };
+        
+        let client = self.prepare(&request).await?;
+        let tokens = self.provider.stream(client, &self.config, &request).await?;
         
-        let _request = LLMRequest {
+        // Charge the reply, and cache it if it completed, once the stream has ended
+        let end = StreamEnd {
             model: self.config.model.clone(),
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: prompt.to_string(),
-            }],
-            max_tokens: self.config.max_tokens,
-            temperature: self.config.temperature,
+            prompt_tokens: self.tokenizer.count_conversation_tokens(&messages) as u32,
+            tokenizer: self.tokenizer,
+            prices: self.prices.clone(),
+            total_cost: self.total_cost.clone(),
+            budget: self.config.budget.clone(),
+            cache: self.config.cache.clone().zip(key),
+        };
+        let usage = tokens.usage.clone();
+        let state = (tokens, String::new(), false, Some(end));
+        
+        let deltas = stream::unfold(state, |(mut tokens, mut text, mut failed, mut end)| async move {
+            match tokens.next().await {
+                Some(chunk) => {
+                    match &chunk {
+                        Ok(delta) => text.push_str(delta),
+                        Err(_) => failed = true,
+                    }
+                    Some((chunk, (tokens, text, failed, end)))
+                }
+                None => {
+                    if let Some(end) = end.take() {
+                        end.finish(text, tokens.usage(), failed);
+                    }
+                    None
+                }
+            }
+        });
+        Ok(TokenStream::with_usage(deltas, usage))
+    }
+    
+    /// Send a request through the provider
+    async fn send(&self, request: &ChatRequest<'_>) -> Result<Completion> {
+        let request = &self.for_model(request);
+        self.ready_client()?;
+        self.check_features(request)?;
+        let key = match self.cache_lookup(request) {
+            CacheLookup::Hit(completion) => return Ok(completion),
+            CacheLookup::Miss(key) => Some(key),
+            CacheLookup::Skip => None,
         };
         
-        // TODO: Implement actual API call
-        // For now, return a mock response
-        info!("Generating response for prompt: {}", prompt);
+        let client = self.prepare(request).await?;
+        let mut completion = self.provider.send(client, &self.config, request).await?;
+        
+        if completion.is_truncated() {
+            warn!("Response was truncated at max_tokens ({:?})", self.config.max_tokens);
+        }
+        
+        self.record_cost(&mut completion);
+        if let (Some(cache), Some(key)) = (&self.config.cache, key) {
+            if let Err(e) = cache.put(&key, &completion) {
+                warn!("Failed to write response cache: {}", e);
+            }
+        }
+        Ok(completion)
+    }
+    
+    /// Fill in what the catalog knows about the model
+    fn for_model<'a>(&self, request: &ChatRequest<'a>) -> ChatRequest<'a> {
+        ChatRequest {
+            reasoning: self.model_info().is_some_and(|info| info.reasoning),
+            ..*request
+        }
+    }
+    
+    /// Look up a request in the response cache
+    /// 
+    /// Cache failures are logged and treated as if there were no cache.
+    fn cache_lookup(&self, request: &ChatRequest<'_>) -> CacheLookup {
+        let Some(cache) = self.config.cache.as_ref().filter(|cache| cache.applies_to(&self.config)) else {
+            return CacheLookup::Skip;
+        };
+        
+        let key = ResponseCache::key(&self.config, request);
+        match cache.get(&key) {
+            Ok(Some(mut completion)) => {
+                info!("Serving response from cache");
+                // Nothing was spent on this call
+                completion.cost = Some(0.0);
+                CacheLookup::Hit(completion)
+            }
+            Ok(None) => CacheLookup::Miss(key),
+            Err(e) => {
+                warn!("Failed to read response cache: {}", e);
+                CacheLookup::Skip
+            }
+        }
+    }
+    
+    /// Shorten a conversation with the configured truncation strategy
+    /// 
+    /// Apart from the sliding window, strategies only kick in when the
+    /// history does not fit the context window of a model in the catalog.
+    async fn fit_conversation(&self, conversation: &mut Conversation) -> Result<()> {
+        if let TruncationStrategy::SlidingWindow { turns } = self.config.truncation {
+            let dropped = keep_recent_turns(conversation, turns);
+            if dropped > 0 {
+                info!("Sliding window dropped {} turns", dropped);
+            }
+        }
+        
+        let Some(limit) = self.prompt_token_limit() else {
+            return Ok(());
+        };
+        let fits = |messages: &[Message]| self.tokenizer.count_conversation_tokens(messages) <= limit;
+        if fits(conversation.messages()) {
+            return Ok(());
+        }
+        
+        match self.config.truncation {
+            TruncationStrategy::Disabled | TruncationStrategy::SlidingWindow { .. } => {}
+            TruncationStrategy::DropOldest => {
+                let dropped = drop_oldest_turns(conversation, fits);
+                info!("Dropped {} turns to fit the context window", dropped);
+            }
+            TruncationStrategy::Summarize { keep_turns } => {
+                if let Some(transcript) = older_turns_transcript(conversation, keep_turns) {
+                    let summarizer = self.summarizer.as_deref().unwrap_or(self);
+                    info!("Summarizing older turns via {}", summarizer.model_name());
+                    
+                    let messages = [Message::system(SUMMARY_INSTRUCTIONS), Message::user(transcript)];
+                    let summary = summarizer.send(&ChatRequest::new(&messages)).await?;
+                    replace_older_turns(conversation, keep_turns, summary.text());
+                }
+                
+                // The turns kept verbatim may still be too long on their own
+                if !fits(conversation.messages()) {
+                    let dropped = drop_oldest_turns(conversation, fits);
+                    info!("Dropped {} turns to fit the context window", dropped);
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Prompt tokens that leave room for the reply in the model's context window
+    fn prompt_token_limit(&self) -> Option<usize> {
+        let info = self.model_info()?;
+        let reserved = self.config.max_tokens.unwrap_or(0);
+        Some(info.context_window.saturating_sub(reserved) as usize)
+    }
+    
+    /// Price a completion and add it to the running totals
+    fn record_cost(&self, completion: &mut Completion) {
+        if let Some(usage) = &completion.usage {
+            completion.cost = self.charge(&self.config.model, usage);
+        }
+    }
+    
+    /// Price a call to `model` and add it to the running totals
+    fn charge(&self, model: &str, usage: &Usage) -> Option<f64> {
+        charge(&self.prices, &self.total_cost, self.config.budget.as_deref(), model, usage)
+    }
+    
+    /// Run the checks and waits every call goes through before it is sent
+    async fn prepare(&self, request: &ChatRequest<'_>) -> Result<&reqwest::Client> {
+        let client = self.ready_client()?;
+        
+        if let Some(budget) = &self.config.budget {
+            budget.check()?;
+        }
+        
+        if let Some(limiter) = &self.config.rate_limiter {
+            let tokens = estimate_request_tokens(&self.tokenizer, request.messages, self.config.max_tokens);
+            limiter.acquire(tokens).await;
+        }
+        
+        Ok(client)
+    }
+    
+    /// Fail when a request uses a feature the model lacks
+    /// 
+    /// Runs before the cache lookup so a cached reply never hides the error.
+    fn check_features(&self, request: &ChatRequest<'_>) -> Result<()> {
+        if !request.tools.is_empty() {
+            self.require(Feature::Tools)?;
+        }
+        if request.response_schema.is_some() {
+            self.require(Feature::Json)?;
+        }
+        if request.messages.iter().any(|message| !message.images.is_empty()) {
+            self.require(Feature::Vision)?;
+        }
+        Ok(())
+    }
+    
+    /// Fail early when the model is known not to support a feature
+    fn require(&self, feature: Feature) -> Result<()> {
+        match self.model_info() {
+            Some(info) if !info.supports(feature) => Err(Error::UnsupportedFeature {
+                model: self.config.model.clone(),
+                feature,
+            }),
+            _ => Ok(()),
+        }
+    }
+    
+    /// Check if the base URL points away from the provider's public API
+    /// 
+    /// Providers without a public API, such as Azure, never count: their
+    /// base URL is the resource that checks the key.
+    fn is_custom_server(&self) -> bool {
+        let host = |url: &str| reqwest::Url::parse(url).ok().and_then(|url| url.host_str().map(str::to_string));
+        let default_url = self.provider.default_base_url();
+        !default_url.is_empty()
+            && self.config.base_url.as_deref().is_some_and(|url| host(url) != host(default_url))
+    }
+    
+    /// Check the interface is ready to make requests and get its HTTP client
+    fn ready_client(&self) -> Result<&reqwest::Client> {
+        if !self.is_connected {
+            return Err(Error::NotInitialized);
+        }
+        
+        // Self-hosted servers usually run without authentication
+        let self_hosted = self.provider.is_self_hosted() || self.is_custom_server();
+        let replaying = self.config.cassette.as_ref().is_some_and(|c| c.mode() == CassetteMode::Replay);
+        if self.config.api_key.is_none() && !self_hosted && !replaying {
+            return Err(Error::MissingApiKey);
+        }
+        
+        self.client.as_ref()
+            .ok_or(Error::NotInitialized)
+    }
+    
+    /// Embed texts with the configured embedding model
+    /// 
+    /// Inputs are sent in batches no larger than the provider accepts in
+    /// one request, and the vectors come back in input order. Each batch
+    /// goes through the budget and rate limiter like any other call, and is
+    /// charged from the tokenizer's count when the provider reports no usage.
+    pub async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let model = self.embedding_model()
+            .ok_or_else(|| Error::Unsupported {
+                provider: self.provider.name().to_string(),
+                operation: "embeddings".to_string(),
+            })?;
+        let client = self.ready_client()?;
+        
+        info!("Embedding {} texts with {}", inputs.len(), model);
+        
+        let mut vectors = Vec::with_capacity(inputs.len());
+        for batch in inputs.chunks(self.provider.max_embedding_batch().max(1)) {
+            if let Some(budget) = &self.config.budget {
+                budget.check()?;
+            }
+            let tokens = batch.iter().map(|text| self.tokenizer.count_tokens(text)).sum::<usize>() as u32;
+            if let Some(limiter) = &self.config.rate_limiter {
+                limiter.acquire(tokens).await;
+            }
+            
+            let request = EmbeddingRequest {
+                inputs: batch,
+                model,
+                dimensions: self.config.embedding_dimensions,
+            };
+            let embeddings = self.provider.embed(client, &self.config, &request).await?;
+            if embeddings.vectors.len() != batch.len() {
+                return Err(Error::EmbeddingMismatch {
+                    expected: batch.len(),
+                    actual: embeddings.vectors.len(),
+                });
+            }
+            
+            // Gemini reports no token counts, so charge the local estimate
+            let usage = embeddings.usage.unwrap_or(Usage {
+                prompt_tokens: tokens,
+                total_tokens: tokens,
+                ..Usage::default()
+            });
+            self.charge(model, &usage);
+            vectors.extend(embeddings.vectors);
+        }
         
-        Ok(format!("This is a mock response from {} for: {}", self.config.model, prompt))
+        Ok(vectors)
+    }
+    
+    /// Get the model `embed` uses, if the provider supports embeddings
+    pub fn embedding_model(&self) -> Option<&str> {
+        self.config.embedding_model.as_deref()
+            .or(self.provider.default_embedding_model())
+    }
+    
+    /// List the models the provider can serve
+    pub async fn list_models(&self) -> Result<Vec<String>> {
+        let client = self.ready_client()?;
+        self.provider.list_models(client, &self.config).await
+    }
+    
+    /// Check that a self-hosted server is up and has the model
+    /// 
+    /// Hosted providers have nothing to check.
+    pub async fn verify(&self) -> Result<()> {
+        let client = self.client.as_ref().ok_or(Error::NotInitialized)?;
+        self.provider.verify(client, &self.config).await
+    }
+    
+    /// Initialize, then verify the server, staying disconnected if it fails
+    pub async fn initialize_async(&mut self) -> Result<()> {
+        self.initialize()?;
+        if let Err(e) = self.verify().await {
+            self.client = None;
+            self.is_connected = false;
+            return Err(e);
+        }
+        Ok(())
+    }
+    
+    /// Get the name of the active provider
+    pub fn provider_name(&self) -> &str {
+        self.provider.name()
+    }
+    
+    /// Get the current configuration
+    pub fn config(&self) -> &LLMConfig {
+        &self.config
+    }
+    
+    /// Get the catalog entry of the current model, if it is known
+    pub fn model_info(&self) -> Option<&ModelInfo> {
+        self.catalog.get(&self.config.model)
+    }
+    
+    /// Get the model catalog
+    pub fn catalog(&self) -> &ModelCatalog {
+        &self.catalog
     }
     
     /// Get the current model name
@@ -114,12 +1319,133 @@ impl LLMInterface {
         &self.config.model
     }
     
+    /// Count the tokens in a piece of text for the configured model
+    pub fn count_tokens(&self, text: &str) -> usize {
+        self.tokenizer.count_tokens(text)
+    }
+    
+    /// Count the prompt tokens a conversation takes for the configured model
+    pub fn count_conversation_tokens(&self, conversation: &Conversation) -> usize {
+        self.tokenizer.count_conversation_tokens(conversation.messages())
+    }
+    
+    /// Get the tokenizer for the configured model
+    pub fn tokenizer(&self) -> &Tokenizer {
+        &self.tokenizer
+    }
+    
+    /// Get the dollars spent by this interface
+    /// 
+    /// Streamed replies are counted once their stream has ended.
+    pub fn total_cost(&self) -> f64 {
+        *self.total_cost.lock().unwrap()
+    }
+    
+    /// Get the price table used for cost accounting
+    pub fn prices(&self) -> &PriceTable {
+        &self.prices
+    }
+    
     /// Check if the interface is connected
     pub fn is_connected(&self) -> bool {
         self.is_connected
     }
 }
 
+/// Price a call to `model` and add it to the running totals
+fn charge(
+    prices: &PriceTable,
+    total_cost: &Mutex<f64>,
+    budget: Option<&Budget>,
+    model: &str,
+    usage: &Usage,
+) -> Option<f64> {
+    let Some(cost) = prices.cost(model, usage) else {
+        warn!("No price known for model {}; call not counted against the budget", model);
+        return None;
+    };
+    
+    *total_cost.lock().unwrap() += cost;
+    if let Some(budget) = budget {
+        budget.record(cost);
+    }
+    Some(cost)
+}
+
+/// Bookkeeping for a streamed reply, done once the stream has ended
+struct StreamEnd {
+    model: String,
+    prompt_tokens: u32,
+    tokenizer: Tokenizer,
+    prices: PriceTable,
+    total_cost: Arc<Mutex<f64>>,
+    budget: Option<Arc<Budget>>,
+    cache: Option<(Arc<ResponseCache>, String)>,
+}
+
+impl StreamEnd {
+    /// Charge the reply and cache it unless the stream failed
+    /// 
+    /// Usage reported by the provider is used when there is any; otherwise
+    /// the prompt and the streamed text are counted with the tokenizer.
+    fn finish(self, text: String, reported: Option<Usage>, failed: bool) {
+        let usage = reported.unwrap_or_else(|| {
+            let completion_tokens = self.tokenizer.count_tokens(&text) as u32;
+            Usage {
+                prompt_tokens: self.prompt_tokens,
+                completion_tokens,
+                total_tokens: self.prompt_tokens + completion_tokens,
+                cached_tokens: 0,
+            }
+        });
+        let cost = charge(&self.prices, &self.total_cost, self.budget.as_deref(), &self.model, &usage);
+        
+        let Some((cache, key)) = self.cache.filter(|_| !failed) else {
+            return;
+        };
+        let completion = Completion {
+            id: None,
+            model: Some(self.model),
+            message: Message::assistant(text),
+            finish_reason: None,
+            usage: Some(usage),
+            cost,
+        };
+        if let Err(e) = cache.put(&key, &completion) {
+            warn!("Failed to write response cache: {}", e);
+        }
+    }
+}
+
+/// Parse, validate and decode a structured reply
+/// 
+/// Returns a description of the problem when the reply is unusable.
+fn parse_structured<T: DeserializeOwned>(
+    content: &str,
+    validator: &jsonschema::Validator,
+) -> std::result::Result<T, String> {
+    // Models sometimes wrap JSON in a markdown code fence
+    let json = content.trim();
+    let json = json
+        .strip_prefix("```json")
+        .or_else(|| json.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(json);
+    
+    let value: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| format!("invalid JSON ({})", e))?;
+    
+    let errors: Vec<String> = validator
+        .iter_errors(&value)
+        .map(|e| e.to_string())
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
 impl NamedComponent for LLMInterface {
     fn get_name(&self) -> &str {
         &self.name
@@ -134,14 +1460,52 @@ impl AIComponent for LLMInterface {
     fn initialize(&mut self) -> Result<()> {
         info!("Initializing LLM Interface for model: {}", self.config.model);
         
+        // Providers without a shared endpoint, such as Azure, need one configured
+        if self.provider.default_base_url().is_empty() && self.config.base_url.is_none() {
+            return Err(Error::MissingBaseUrl(self.provider.name().to_string()));
+        }
+        
+        // Validate the model against the catalog; self-hosted servers
+        // serve models the catalog cannot know about
+        match self.catalog.get(&self.config.model) {
+            Some(info) => {
+                // Azure deployments serve the OpenAI models
+                let provider = self.config.provider;
+                let served = provider == info.provider
+                    || (provider == ProviderKind::Azure && info.provider == ProviderKind::OpenAI);
+                if !served && !self.provider.is_self_hosted() {
+                    // A gateway behind a custom base URL may route the model elsewhere
+                    if self.config.base_url.is_some() {
+                        warn!("Model {} is served by {:?}, not {:?}", self.config.model, info.provider, provider);
+                    } else {
+                        return Err(Error::ProviderMismatch {
+                            model: self.config.model.clone(),
+                            provider,
+                            expected: info.provider,
+                        });
+                    }
+                }
+                
+                let limit = info.max_output_tokens;
+                if self.config.max_tokens.is_some_and(|max_tokens| max_tokens > limit) {
+                    warn!("Clamping max_tokens to {} for model {}", limit, self.config.model);
+                    self.config.max_tokens = Some(limit);
+                }
+            }
+            None if self.config.base_url.is_none() && !self.provider.is_self_hosted() => {
+                return Err(Error::UnknownModel(self.config.model.clone()));
+            }
+            None => warn!("Model {} is not in the catalog; skipping validation", self.config.model),
+        }
+        
         // Create HTTP client
-        self.client = Some(reqwest::Client::new());
+        let client = reqwest::Client::new();
         
         // TODO: Add actual API initialization
         // - Validate API key format
-        // - Test connection to API endpoint
         // - Set up HTTP client configuration
         
+        self.client = Some(client);
         self.is_connected = true;
         info!("LLM Interface initialized successfully");
         Ok(())
@@ -150,7 +1514,7 @@ impl AIComponent for LLMInterface {
     fn process(&self) -> Result<()> {
         if !self.is_connected {
             warn!("LLM Interface not connected. Please initialize first.");
-            return Err(anyhow::anyhow!("Interface not connected"));
+            return Err(Error::NotInitialized);
         }
         
         info!("Processing with LLM model: {}", self.config.model);
@@ -164,4 +1528,4 @@ impl AIComponent for LLMInterface {
     fn description(&self) -> &str {
         self.get_description()
     }
-} 
\ No newline at end of file
+}
@@ -2,13 +2,61 @@
 //! 
 //! This module contains the core AI components including:
 //! - Base AIComponent trait
+//! - On-disk response cache
+//! - Model catalog with context windows and capabilities
+//! - Record/replay cassettes for provider HTTP traffic
+//! - Conversation history for multi-turn chats
+//! - Document loading and token-based chunking for retrieval
 //! - LLMInterface for API interactions
 //! - PromptManager for template management
+//! - Provider backends used by the LLMInterface
+//! - Price table and spending budget
+//! - Retrieval-augmented generation over a vector store
+//! - Client-side rate limiting
+//! - Retry policy for transient API failures
+//! - Offline tokenizer for token counting
+//! - Tool registry for function calling
+//! - Context-window truncation strategies for conversations
+//! - In-memory vector store for similarity search
 
 pub mod ai_component;
+pub mod cache;
+pub mod cassette;
+pub mod catalog;
+pub mod conversation;
+pub mod documents;
 pub mod llm_interface;
 pub mod prompt_manager;
+pub mod pricing;
+pub mod providers;
+pub mod rag;
+pub mod rate_limiter;
+pub mod retry;
+pub mod tokenizer;
+pub mod tools;
+pub mod truncation;
+pub mod vector_store;
 
 pub use ai_component::{AIComponent, NamedComponent};
-pub use llm_interface::LLMInterface;
-pub use prompt_manager::PromptManager; 
\ No newline at end of file
+pub use cache::ResponseCache;
+pub use cassette::{Cassette, CassetteMode};
+pub use catalog::{Feature, ModelCatalog, ModelInfo};
+pub use conversation::Conversation;
+pub use documents::{Chunk, Chunker, DocumentKind, DocumentLoader, SourceDocument};
+pub use llm_interface::{
+    ChatRequest, Completion, EmbeddingRequest, Embeddings, FinishReason, Image, LLMConfig, LLMInterface,
+    Message, ResponseSchema, Role, TokenStream, Tool, ToolCall, Usage,
+};
+pub use prompt_manager::PromptManager;
+pub use pricing::{Budget, ModelPrice, PriceTable};
+pub use providers::{
+    AzureOpenAIProvider, AzureOptions, GeminiProvider, LLMProvider, MockError, MockProvider,
+    MockResponse, OllamaEndpoint, OllamaOptions, OllamaProvider, ProviderKind,
+};
+pub use rag::{RagAnswer, RagPipeline};
+pub use rate_limiter::RateLimiter;
+pub use retry::RetryPolicy;
+pub use tokenizer::{Encoding, Tokenizer};
+pub use tools::{ToolRegistry, ToolResult};
+pub use truncation::TruncationStrategy;
+pub use vector_store::{Document, Metadata, MetadataFilter, SearchResult, Similarity, VectorStore}; 
\ No newline at end of file
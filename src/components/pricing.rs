@@ -0,0 +1,178 @@
+use crate::components::catalog::{lookup, ModelCatalog};
+use crate::components::llm_interface::Usage;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Price of a model in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    /// Price of prompt tokens served from the prompt cache; `input` when unset
+    #[serde(default)]
+    pub cached_input: Option<f64>,
+}
+
+impl ModelPrice {
+    /// Create a price from input and output rates per million tokens
+    pub fn new(input: f64, output: f64) -> Self {
+        Self {
+            input,
+            output,
+            cached_input: None,
+        }
+    }
+    
+    /// Set a discounted rate for cached prompt tokens
+    pub fn with_cached_input(mut self, cached_input: f64) -> Self {
+        self.cached_input = Some(cached_input);
+        self
+    }
+    
+    /// Dollar cost of a call with the given usage
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        let cached = usage.cached_tokens.min(usage.prompt_tokens) as f64;
+        let uncached = usage.prompt_tokens as f64 - cached;
+        
+        (uncached * self.input
+            + cached * self.cached_input.unwrap_or(self.input)
+            + usage.completion_tokens as f64 * self.output)
+            / 1_000_000.0
+    }
+}
+
+/// Prices keyed by model name
+/// 
+/// Lookups fall back to the longest key followed by a `-` in the model
+/// name, so dated snapshots such as `gpt-4o-2024-08-06` or `claude-3-5-haiku-latest`
+/// use the price of their family.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceTable {
+    prices: HashMap<String, ModelPrice>,
+}
+
+impl PriceTable {
+    /// Create a table with no prices
+    pub fn empty() -> Self {
+        Self {
+            prices: HashMap::new(),
+        }
+    }
+    
+    /// Add or override the price of a model
+    pub fn set(&mut self, model: impl Into<String>, price: ModelPrice) {
+        self.prices.insert(model.into(), price);
+    }
+    
+    /// Look up the price of a model
+    pub fn get(&self, model: &str) -> Option<&ModelPrice> {
+        lookup(&self.prices, model)
+    }
+    
+    /// Dollar cost of a call, or `None` when the model has no price
+    pub fn cost(&self, model: &str, usage: &Usage) -> Option<f64> {
+        self.get(model).map(|price| price.cost(usage))
+    }
+}
+
+impl Default for PriceTable {
+    /// Table with the list prices of the built-in model catalog
+    fn default() -> Self {
+        ModelCatalog::built_in().price_table()
+    }
+}
+
+#[derive(Debug)]
+struct Spend {
+    limit: Option<f64>,
+    spent: f64,
+    calls: u64,
+}
+
+/// Running dollar spend with an optional limit
+/// 
+/// `LLMInterface` refuses to make calls once the spend reaches the limit.
+/// Wrap it in an `Arc` and hand it to several interfaces to cap a whole
+/// session.
+#[derive(Debug)]
+pub struct Budget {
+    spend: Mutex<Spend>,
+}
+
+impl Budget {
+    /// Create a budget capped at `limit` dollars; `None` only tracks spend
+    pub fn new(limit: Option<f64>) -> Self {
+        Self {
+            spend: Mutex::new(Spend {
+                limit,
+                spent: 0.0,
+                calls: 0,
+            }),
+        }
+    }
+    
+    /// Create a budget that only tracks spend
+    pub fn unlimited() -> Self {
+        Self::new(None)
+    }
+    
+    /// Change the limit; `None` removes it
+    pub fn set_limit(&self, limit: Option<f64>) {
+        self.spend.lock().unwrap().limit = limit;
+    }
+    
+    /// Get the limit in dollars
+    pub fn limit(&self) -> Option<f64> {
+        self.spend.lock().unwrap().limit
+    }
+    
+    /// Get the dollars spent so far
+    pub fn spent(&self) -> f64 {
+        self.spend.lock().unwrap().spent
+    }
+    
+    /// Get the dollars left before the limit, if there is one
+    pub fn remaining(&self) -> Option<f64> {
+        let spend = self.spend.lock().unwrap();
+        spend.limit.map(|limit| (limit - spend.spent).max(0.0))
+    }
+    
+    /// Get the number of priced calls recorded
+    pub fn calls(&self) -> u64 {
+        self.spend.lock().unwrap().calls
+    }
+    
+    /// Fail with `Error::BudgetExceeded` once the limit has been reached
+    pub fn check(&self) -> Result<()> {
+        let spend = self.spend.lock().unwrap();
+        match spend.limit {
+            Some(limit) if spend.spent >= limit => Err(Error::BudgetExceeded {
+                spent: spend.spent,
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+    
+    /// Add the cost of a call
+    pub fn record(&self, cost: f64) {
+        let mut spend = self.spend.lock().unwrap();
+        spend.spent += cost;
+        spend.calls += 1;
+    }
+    
+    /// Forget the spend so far, keeping the limit
+    pub fn reset(&self) {
+        let mut spend = self.spend.lock().unwrap();
+        spend.spent = 0.0;
+        spend.calls = 0;
+    }
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
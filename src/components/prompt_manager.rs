@@ -1,5 +1,5 @@
 use crate::components::{AIComponent, NamedComponent};
-use anyhow::Result;
+use crate::{Error, Result};
 use tracing::{info, warn};
 use std::collections::HashMap;
 
@@ -53,7 +53,7 @@ impl PromptManager {
     pub fn add_template(&mut self, template: String) -> Result<()> {
         if template.trim().is_empty() {
             warn!("Cannot add empty template");
-            return Err(anyhow::anyhow!("Template cannot be empty"));
+            return Err(Error::InvalidTemplate("Template cannot be empty".to_string()));
         }
         
         self.prompt_templates.push(template.clone());
@@ -93,6 +93,14 @@ impl PromptManager {
         result
     }
     
+    /// Process a template, failing if any placeholder has no value
+    /// 
+    /// Placeholders are replaced in a single pass, so a value containing
+    /// `{name}` is inserted as written.
+    pub fn render_template(&self, template: &str) -> Result<String> {
+        render(template, &self.template_variables)
+    }
+    
     /// Get a processed template by index
     pub fn get_processed_template(&self, index: usize) -> Option<String> {
         self.get_template(index)
@@ -100,6 +108,57 @@ impl PromptManager {
     }
 }
 
+/// Fill a template, failing if any placeholder has no value
+pub(crate) fn render(template: &str, variables: &HashMap<String, String>) -> Result<String> {
+    match fill(template, variables) {
+        (_, Some(name)) => Err(Error::MissingVariable(name.to_string())),
+        (result, None) => Ok(result),
+    }
+}
+
+/// Replace `{key}` placeholders, scanning the template once
+/// 
+/// Any text between braces that is a variable name is replaced. Returns
+/// the filled template and the first placeholder with no value; braces
+/// around anything but a name, such as JSON, are not placeholders.
+fn fill<'a>(template: &'a str, variables: &HashMap<String, String>) -> (String, Option<&'a str>) {
+    let mut result = String::with_capacity(template.len());
+    let mut missing = None;
+    let mut rest = template;
+    
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let name = match after.find(['{', '}']) {
+            Some(end) if after[end..].starts_with('}') => &after[..end],
+            _ => {
+                result.push('{');
+                rest = after;
+                continue;
+            }
+        };
+        
+        match variables.get(name) {
+            Some(value) => result.push_str(value),
+            None => {
+                if is_placeholder_name(name) {
+                    missing.get_or_insert(name);
+                }
+                result.push_str(&rest[start..start + name.len() + 2]);
+            }
+        }
+        rest = &after[name.len() + 1..];
+    }
+    
+    result.push_str(rest);
+    (result, missing)
+}
+
+/// Check if braced text looks like a variable such as `topic` or `user-name`
+fn is_placeholder_name(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
 impl Default for PromptManager {
     fn default() -> Self {
         Self::new()
@@ -139,7 +198,7 @@ impl AIComponent for PromptManager {
             }
             None => {
                 warn!("No current prompt set");
-                Err(anyhow::anyhow!("No current prompt"))
+                Err(Error::NoCurrentPrompt)
             }
         }
     }
@@ -0,0 +1,405 @@
+use super::sse::{self, SseEvent, StreamEvent};
+use super::{post, send_checked, send_request, LLMProvider};
+use crate::components::llm_interface::{
+    ChatRequest, Completion, FinishReason, Image, LLMConfig, Message, Role, TokenStream, Tool,
+    ToolCall, Usage,
+};
+use crate::{Error, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Description of the tool used to request structured output
+const STRUCTURED_OUTPUT_DESCRIPTION: &str = "Respond with structured output matching this schema";
+
+/// Default API root for Anthropic
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com/v1";
+
+/// API version sent in the `anthropic-version` header
+const API_VERSION: &str = "2023-06-01";
+
+/// Output limit used when the config does not set one (the API requires it)
+const DEFAULT_MAX_TOKENS: u32 = 1024;
+
+/// Request structure for the Messages API
+#[derive(Debug, Serialize)]
+struct MessagesRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    messages: Vec<AnthropicMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<AnthropicTool<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<ToolChoice<'a>>,
+}
+
+/// Forces the model to call a specific tool
+#[derive(Debug, Serialize)]
+struct ToolChoice<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    name: &'a str,
+}
+
+/// Message in the Messages API format
+#[derive(Debug, Serialize)]
+struct AnthropicMessage {
+    role: &'static str,
+    content: MessageContent,
+}
+
+/// Message content, either plain text or a list of blocks
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum MessageContent {
+    Text(String),
+    Blocks(Vec<RequestBlock>),
+}
+
+/// A single block of request content
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RequestBlock {
+    Text { text: String },
+    Image { source: ImageSource },
+    ToolUse { id: String, name: String, input: serde_json::Value },
+    ToolResult { tool_use_id: String, content: String },
+}
+
+/// Where the data of an image block comes from
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ImageSource {
+    Base64 { media_type: String, data: String },
+    Url { url: String },
+}
+
+impl From<&Image> for ImageSource {
+    fn from(image: &Image) -> Self {
+        match image.inline_data() {
+            Some((media_type, data)) => ImageSource::Base64 {
+                media_type: media_type.to_string(),
+                data: data.to_string(),
+            },
+            None => ImageSource::Url { url: image.url.clone() },
+        }
+    }
+}
+
+/// Tool definition in the Messages API format
+#[derive(Debug, Serialize)]
+struct AnthropicTool<'a> {
+    name: &'a str,
+    description: &'a str,
+    input_schema: &'a serde_json::Value,
+}
+
+impl<'a> From<&'a Tool> for AnthropicTool<'a> {
+    fn from(tool: &'a Tool) -> Self {
+        Self {
+            name: &tool.name,
+            description: &tool.description,
+            input_schema: &tool.parameters,
+        }
+    }
+}
+
+/// Response structure from the Messages API
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    id: Option<String>,
+    model: Option<String>,
+    content: Vec<ContentBlock>,
+    stop_reason: Option<String>,
+    usage: Option<MessagesUsage>,
+}
+
+/// Usage block in the Messages API format
+/// 
+/// `input_tokens` excludes tokens read from or written to the prompt cache.
+#[derive(Debug, Deserialize)]
+struct MessagesUsage {
+    /// Absent from the usage sent with `message_delta` stream events
+    #[serde(default)]
+    input_tokens: u32,
+    output_tokens: u32,
+    #[serde(default)]
+    cache_read_input_tokens: Option<u32>,
+    #[serde(default)]
+    cache_creation_input_tokens: Option<u32>,
+}
+
+impl From<MessagesUsage> for Usage {
+    fn from(usage: MessagesUsage) -> Self {
+        let cached_tokens = usage.cache_read_input_tokens.unwrap_or(0);
+        let prompt_tokens = usage.input_tokens
+            + cached_tokens
+            + usage.cache_creation_input_tokens.unwrap_or(0);
+        
+        Self {
+            prompt_tokens,
+            completion_tokens: usage.output_tokens,
+            total_tokens: prompt_tokens + usage.output_tokens,
+            cached_tokens,
+        }
+    }
+}
+
+/// A single block of response content
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock {
+    Text { text: String },
+    ToolUse { id: String, name: String, input: serde_json::Value },
+    #[serde(other)]
+    Other,
+}
+
+/// Event sent while streaming a message
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamingEvent {
+    MessageStart { message: StreamingMessage },
+    ContentBlockDelta { delta: BlockDelta },
+    MessageDelta { usage: Option<MessagesUsage> },
+    MessageStop,
+    Error { error: StreamError },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BlockDelta {
+    TextDelta { text: String },
+    #[serde(other)]
+    Other,
+}
+
+/// Message header sent when a stream starts
+#[derive(Debug, Deserialize)]
+struct StreamingMessage {
+    usage: Option<MessagesUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamError {
+    message: String,
+}
+
+/// Anthropic Messages API backend
+#[derive(Debug, Clone, Default)]
+pub struct AnthropicProvider;
+
+impl AnthropicProvider {
+    /// Convert messages to the Messages API format
+    /// 
+    /// System prompts are returned separately since they are a top-level
+    /// field. Tool results become `tool_result` blocks in a user turn, with
+    /// consecutive results merged so user and assistant turns alternate.
+    fn convert_messages(messages: &[Message]) -> (Option<String>, Vec<AnthropicMessage>) {
+        let mut system = Vec::new();
+        let mut converted: Vec<AnthropicMessage> = Vec::new();
+        
+        for message in messages {
+            match message.role {
+                Role::System => system.push(message.content.as_str()),
+                Role::User if message.images.is_empty() => converted.push(AnthropicMessage {
+                    role: "user",
+                    content: MessageContent::Text(message.content.clone()),
+                }),
+                Role::User => {
+                    // Images go before the text that refers to them
+                    let images = message.images.iter().map(|image| RequestBlock::Image { source: image.into() });
+                    let text = RequestBlock::Text { text: message.content.clone() };
+                    converted.push(AnthropicMessage {
+                        role: "user",
+                        content: MessageContent::Blocks(images.chain(std::iter::once(text)).collect()),
+                    });
+                }
+                Role::Assistant if message.tool_calls.is_empty() => converted.push(AnthropicMessage {
+                    role: "assistant",
+                    content: MessageContent::Text(message.content.clone()),
+                }),
+                Role::Assistant => {
+                    let text = (!message.content.is_empty()).then(|| RequestBlock::Text {
+                        text: message.content.clone(),
+                    });
+                    let tool_uses = message.tool_calls.iter().map(|call| RequestBlock::ToolUse {
+                        id: call.id.clone(),
+                        name: call.name.clone(),
+                        input: call.arguments.clone(),
+                    });
+                    converted.push(AnthropicMessage {
+                        role: "assistant",
+                        content: MessageContent::Blocks(text.into_iter().chain(tool_uses).collect()),
+                    });
+                }
+                Role::Tool => {
+                    let result = RequestBlock::ToolResult {
+                        tool_use_id: message.tool_call_id.clone().unwrap_or_default(),
+                        content: message.content.clone(),
+                    };
+                    match converted.last_mut() {
+                        Some(AnthropicMessage { role: "user", content: MessageContent::Blocks(blocks) }) => {
+                            blocks.push(result);
+                        }
+                        _ => converted.push(AnthropicMessage {
+                            role: "user",
+                            content: MessageContent::Blocks(vec![result]),
+                        }),
+                    }
+                }
+            }
+        }
+        
+        let system = (!system.is_empty()).then(|| system.join("\n\n"));
+        (system, converted)
+    }
+    
+    /// Build the HTTP request for a message
+    fn build_request(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+        stream: bool,
+    ) -> reqwest::RequestBuilder {
+        let (system, messages) = Self::convert_messages(request.messages);
+        let mut tools: Vec<AnthropicTool> = request.tools.iter().map(Into::into).collect();
+        
+        // There is no JSON schema mode, so structured output is requested
+        // by forcing a call to a tool whose input schema is the target schema
+        let tool_choice = request.response_schema.map(|schema| {
+            tools.push(AnthropicTool {
+                name: &schema.name,
+                description: STRUCTURED_OUTPUT_DESCRIPTION,
+                input_schema: &schema.schema,
+            });
+            ToolChoice {
+                kind: "tool",
+                name: &schema.name,
+            }
+        });
+        
+        let body = MessagesRequest {
+            model: &config.model,
+            max_tokens: config.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            system,
+            messages,
+            temperature: config.temperature,
+            stream: stream.then_some(true),
+            tools,
+            tool_choice,
+        };
+        
+        let mut http_request = post(client, config, DEFAULT_BASE_URL, "messages")
+            .header("anthropic-version", API_VERSION);
+        if let Some(api_key) = &config.api_key {
+            http_request = http_request.header("x-api-key", api_key);
+        }
+        
+        http_request.json(&body)
+    }
+    
+    /// Extract the text delta from a streamed event
+    fn parse_event(event: &SseEvent) -> Result<Vec<StreamEvent>> {
+        let event: StreamingEvent = serde_json::from_str(&event.data)
+            .map_err(|e| Error::Stream(format!("Failed to parse stream event: {}", e)))?;
+        
+        // Input tokens arrive with `message_start`, output tokens with `message_delta`
+        match event {
+            StreamingEvent::MessageStart { message: StreamingMessage { usage } }
+            | StreamingEvent::MessageDelta { usage } => {
+                Ok(usage.map(|usage| StreamEvent::Usage(usage.into())).into_iter().collect())
+            }
+            StreamingEvent::ContentBlockDelta { delta: BlockDelta::TextDelta { text } } => {
+                Ok(vec![StreamEvent::Delta(text)])
+            }
+            StreamingEvent::MessageStop => Ok(vec![StreamEvent::Done]),
+            StreamingEvent::Error { error } => {
+                Err(Error::Stream(error.message))
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl LLMProvider for AnthropicProvider {
+    fn name(&self) -> &str {
+        "Anthropic"
+    }
+    
+    fn default_base_url(&self) -> &str {
+        DEFAULT_BASE_URL
+    }
+    
+    async fn send(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+    ) -> Result<Completion> {
+        let body = send_request(config, self.build_request(client, config, request, false)).await?;
+        
+        let response: MessagesResponse = serde_json::from_str(&body)?;
+        
+        let mut reply = Message::assistant(String::new());
+        for block in response.content {
+            match block {
+                ContentBlock::Text { text } => reply.content.push_str(&text),
+                ContentBlock::ToolUse { id, name, input } => reply.tool_calls.push(ToolCall {
+                    id,
+                    name,
+                    arguments: input,
+                }),
+                ContentBlock::Other => {}
+            }
+        }
+        
+        // Hand the forced tool input back as the JSON reply content
+        if let Some(schema) = request.response_schema {
+            if let Some(index) = reply.tool_calls.iter().position(|call| call.name == schema.name) {
+                let call = reply.tool_calls.remove(index);
+                reply.content = call.arguments.to_string();
+            }
+        }
+        
+        if reply.content.is_empty() && reply.tool_calls.is_empty() {
+            return Err(Error::EmptyResponse);
+        }
+        
+        // A consumed structured-output call is a finished answer, not a pending tool call
+        let finish_reason = response.stop_reason.as_deref().map(|reason| {
+            match FinishReason::from_provider(reason) {
+                FinishReason::ToolCalls if reply.tool_calls.is_empty() => FinishReason::Stop,
+                reason => reason,
+            }
+        });
+        
+        Ok(Completion {
+            id: response.id,
+            model: response.model,
+            message: reply,
+            finish_reason,
+            usage: response.usage.map(Into::into),
+            cost: None,
+        })
+    }
+    
+    async fn stream(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+    ) -> Result<TokenStream> {
+        let response = send_checked(config, self.build_request(client, config, request, true)).await?;
+        Ok(sse::token_stream(response, Self::parse_event))
+    }
+}
@@ -0,0 +1,121 @@
+use super::openai::{OpenAIProvider, DEFAULT_EMBEDDING_MODEL, MAX_EMBEDDING_BATCH};
+use super::{post, send_checked, send_request, sse, LLMProvider};
+use crate::components::llm_interface::{
+    ChatRequest, Completion, EmbeddingRequest, Embeddings, LLMConfig, TokenStream,
+};
+use crate::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// API version sent when the config does not set one
+pub const DEFAULT_API_VERSION: &str = "2024-10-21";
+
+/// Azure OpenAI deployment settings
+/// 
+/// `LLMConfig::model` keeps naming the underlying model (such as
+/// `gpt-4o`) so the catalog can look up its limits and price, while
+/// requests go to the deployment.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AzureOptions {
+    /// Deployment name; the model name when unset
+    pub deployment: Option<String>,
+    /// `api-version` query parameter; `DEFAULT_API_VERSION` when unset
+    pub api_version: Option<String>,
+    /// Deployment used by `embed`; the embedding model name when unset
+    pub embedding_deployment: Option<String>,
+}
+
+/// Azure OpenAI backend
+/// 
+/// Speaks the OpenAI chat completions format, but addresses deployments
+/// under the resource endpoint set as the base URL, such as
+/// `https://my-resource.openai.azure.com`, and authenticates with an
+/// `api-key` header.
+#[derive(Debug, Clone, Default)]
+pub struct AzureOpenAIProvider;
+
+impl AzureOpenAIProvider {
+    /// Start a request to an operation of a deployment
+    fn deployment_request(
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        deployment: &str,
+        operation: &str,
+    ) -> reqwest::RequestBuilder {
+        let api_version = config.azure.api_version.as_deref().unwrap_or(DEFAULT_API_VERSION);
+        let path = format!("openai/deployments/{}/{}", deployment, operation);
+        
+        let http_request = post(client, config, "", &path).query(&[("api-version", api_version)]);
+        match &config.api_key {
+            Some(api_key) => http_request.header("api-key", api_key),
+            None => http_request,
+        }
+    }
+    
+    /// Build the HTTP request for a chat completion
+    fn build_request(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+        stream: bool,
+    ) -> reqwest::RequestBuilder {
+        let deployment = config.azure.deployment.as_deref().unwrap_or(&config.model);
+        Self::deployment_request(client, config, deployment, "chat/completions")
+            .json(&OpenAIProvider::request_body(config, request, stream))
+    }
+}
+
+#[async_trait]
+impl LLMProvider for AzureOpenAIProvider {
+    fn name(&self) -> &str {
+        "Azure OpenAI"
+    }
+    
+    /// Azure has no shared endpoint; every resource has its own
+    fn default_base_url(&self) -> &str {
+        ""
+    }
+    
+    fn default_embedding_model(&self) -> Option<&str> {
+        Some(DEFAULT_EMBEDDING_MODEL)
+    }
+    
+    fn max_embedding_batch(&self) -> usize {
+        MAX_EMBEDDING_BATCH
+    }
+    
+    async fn embed(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &EmbeddingRequest<'_>,
+    ) -> Result<Embeddings> {
+        let deployment = config.azure.embedding_deployment.as_deref().unwrap_or(request.model);
+        let http_request = Self::deployment_request(client, config, deployment, "embeddings")
+            .json(&OpenAIProvider::embeddings_body(request));
+        
+        let body = send_request(config, http_request).await?;
+        OpenAIProvider::parse_embeddings(&body)
+    }
+    
+    async fn send(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+    ) -> Result<Completion> {
+        let body = send_request(config, self.build_request(client, config, request, false)).await?;
+        OpenAIProvider::parse_completion(&body)
+    }
+    
+    async fn stream(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+    ) -> Result<TokenStream> {
+        let response = send_checked(config, self.build_request(client, config, request, true)).await?;
+        Ok(sse::token_stream(response, OpenAIProvider::parse_event))
+    }
+}
@@ -0,0 +1,467 @@
+use super::sse::{self, SseEvent, StreamEvent};
+use super::{post, send_checked, send_request, LLMProvider};
+use crate::components::llm_interface::{
+    ChatRequest, Completion, EmbeddingRequest, Embeddings, FinishReason, Image, LLMConfig, Message,
+    Role, TokenStream, Tool, ToolCall, Usage,
+};
+use crate::{Error, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Default API root for Gemini
+const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+/// Embedding model used when the config does not name one
+const DEFAULT_EMBEDDING_MODEL: &str = "gemini-embedding-001";
+
+/// Most texts `batchEmbedContents` accepts in one request
+const MAX_EMBEDDING_BATCH: usize = 100;
+
+/// Request structure for `generateContent`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateContentRequest<'a> {
+    contents: Vec<Content>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<Content>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<GeminiTools<'a>>,
+    generation_config: GenerationConfig<'a>,
+}
+
+/// Sampling and output settings
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerationConfig<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_output_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_json_schema: Option<&'a serde_json::Value>,
+}
+
+/// Turn of the conversation, or the system instruction when it has no role
+#[derive(Debug, Serialize, Deserialize)]
+struct Content {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+    #[serde(default)]
+    parts: Vec<Part>,
+}
+
+/// A single part of a turn
+/// 
+/// Parts hold exactly one of their fields; the others are left out.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Part {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    function_call: Option<FunctionCall>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    function_response: Option<FunctionResponse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inline_data: Option<Blob>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    file_data: Option<FileData>,
+}
+
+impl Part {
+    fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: Some(text.into()),
+            ..Self::default()
+        }
+    }
+    
+    /// Inline images are sent as data, others by URI
+    fn image(image: &Image) -> Self {
+        match image.inline_data() {
+            Some((mime_type, data)) => Self {
+                inline_data: Some(Blob {
+                    mime_type: mime_type.to_string(),
+                    data: data.to_string(),
+                }),
+                ..Self::default()
+            },
+            None => Self {
+                file_data: Some(FileData { file_uri: image.url.clone() }),
+                ..Self::default()
+            },
+        }
+    }
+}
+
+/// Base64-encoded data with its media type
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Blob {
+    mime_type: String,
+    data: String,
+}
+
+/// Reference to data stored elsewhere
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileData {
+    file_uri: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FunctionCall {
+    name: String,
+    #[serde(default)]
+    args: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FunctionResponse {
+    name: String,
+    response: serde_json::Value,
+}
+
+/// Tool list entry wrapping the function declarations
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiTools<'a> {
+    function_declarations: Vec<FunctionDeclaration<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct FunctionDeclaration<'a> {
+    name: &'a str,
+    description: &'a str,
+    parameters: &'a serde_json::Value,
+}
+
+impl<'a> From<&'a Tool> for FunctionDeclaration<'a> {
+    fn from(tool: &'a Tool) -> Self {
+        Self {
+            name: &tool.name,
+            description: &tool.description,
+            parameters: &tool.parameters,
+        }
+    }
+}
+
+/// Response from `generateContent`, or one event of its stream
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateContentResponse {
+    #[serde(default)]
+    candidates: Vec<Candidate>,
+    usage_metadata: Option<UsageMetadata>,
+    model_version: Option<String>,
+    response_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Candidate {
+    content: Option<Content>,
+    finish_reason: Option<String>,
+}
+
+/// Usage block in the Gemini format
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageMetadata {
+    #[serde(default)]
+    prompt_token_count: u32,
+    #[serde(default)]
+    candidates_token_count: u32,
+    #[serde(default)]
+    cached_content_token_count: u32,
+}
+
+impl From<UsageMetadata> for Usage {
+    fn from(usage: UsageMetadata) -> Self {
+        Self {
+            prompt_tokens: usage.prompt_token_count,
+            completion_tokens: usage.candidates_token_count,
+            total_tokens: usage.prompt_token_count + usage.candidates_token_count,
+            cached_tokens: usage.cached_content_token_count,
+        }
+    }
+}
+
+/// Request structure for `batchEmbedContents`
+#[derive(Debug, Serialize)]
+struct BatchEmbedRequest {
+    requests: Vec<EmbedContentRequest>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EmbedContentRequest {
+    /// Resource name of the model, such as `models/gemini-embedding-001`
+    model: String,
+    content: Content,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output_dimensionality: Option<u32>,
+}
+
+/// Response from `batchEmbedContents`
+#[derive(Debug, Deserialize)]
+struct BatchEmbedResponse {
+    #[serde(default)]
+    embeddings: Vec<ContentEmbedding>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentEmbedding {
+    values: Vec<f32>,
+}
+
+/// Google Gemini `generateContent` backend
+#[derive(Debug, Clone, Default)]
+pub struct GeminiProvider;
+
+impl GeminiProvider {
+    /// Convert messages to Gemini contents
+    /// 
+    /// System prompts are returned separately as the system instruction.
+    /// Tool results become `functionResponse` parts in a user turn, named
+    /// after the call they answer, with consecutive results merged.
+    fn convert_messages(messages: &[Message]) -> (Option<Content>, Vec<Content>) {
+        let mut system = Vec::new();
+        let mut contents: Vec<Content> = Vec::new();
+        let mut call_names: HashMap<&str, &str> = HashMap::new();
+        
+        for message in messages {
+            match message.role {
+                Role::System => system.push(Part::text(message.content.clone())),
+                Role::User => contents.push(Content {
+                    role: Some("user".to_string()),
+                    parts: message.images.iter()
+                        .map(Part::image)
+                        .chain(std::iter::once(Part::text(message.content.clone())))
+                        .collect(),
+                }),
+                Role::Assistant => {
+                    let text = (!message.content.is_empty()).then(|| Part::text(message.content.clone()));
+                    let calls = message.tool_calls.iter().map(|call| {
+                        call_names.insert(&call.id, &call.name);
+                        Part {
+                            function_call: Some(FunctionCall {
+                                name: call.name.clone(),
+                                args: call.arguments.clone(),
+                            }),
+                            ..Part::default()
+                        }
+                    });
+                    contents.push(Content {
+                        role: Some("model".to_string()),
+                        parts: text.into_iter().chain(calls).collect(),
+                    });
+                }
+                Role::Tool => {
+                    let id = message.tool_call_id.as_deref().unwrap_or_default();
+                    let name = call_names.get(id).copied().unwrap_or(id);
+                    // The response must be an object, so wrap anything else
+                    let response = match serde_json::from_str(&message.content) {
+                        Ok(value @ serde_json::Value::Object(_)) => value,
+                        _ => serde_json::json!({ "content": message.content }),
+                    };
+                    let part = Part {
+                        function_response: Some(FunctionResponse {
+                            name: name.to_string(),
+                            response,
+                        }),
+                        ..Part::default()
+                    };
+                    
+                    match contents.last_mut() {
+                        Some(last) if last.role.as_deref() == Some("user")
+                            && last.parts.iter().all(|p| p.function_response.is_some()) =>
+                        {
+                            last.parts.push(part);
+                        }
+                        _ => contents.push(Content {
+                            role: Some("user".to_string()),
+                            parts: vec![part],
+                        }),
+                    }
+                }
+            }
+        }
+        
+        let system = (!system.is_empty()).then_some(Content { role: None, parts: system });
+        (system, contents)
+    }
+    
+    /// Build the HTTP request for a generation
+    fn build_request(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+        stream: bool,
+    ) -> reqwest::RequestBuilder {
+        let (system_instruction, contents) = Self::convert_messages(request.messages);
+        let tools = match request.tools {
+            [] => Vec::new(),
+            tools => vec![GeminiTools {
+                function_declarations: tools.iter().map(Into::into).collect(),
+            }],
+        };
+        
+        let body = GenerateContentRequest {
+            contents,
+            system_instruction,
+            tools,
+            generation_config: GenerationConfig {
+                temperature: config.temperature,
+                max_output_tokens: config.max_tokens,
+                response_mime_type: request.response_schema.map(|_| "application/json"),
+                response_json_schema: request.response_schema.map(|schema| &schema.schema),
+            },
+        };
+        
+        let path = match stream {
+            true => format!("models/{}:streamGenerateContent?alt=sse", config.model),
+            false => format!("models/{}:generateContent", config.model),
+        };
+        let mut http_request = post(client, config, DEFAULT_BASE_URL, &path);
+        if let Some(api_key) = &config.api_key {
+            http_request = http_request.header("x-goog-api-key", api_key);
+        }
+        
+        http_request.json(&body)
+    }
+    
+    /// Extract the text delta from a streamed event
+    fn parse_event(event: &SseEvent) -> Result<Vec<StreamEvent>> {
+        let chunk: GenerateContentResponse = serde_json::from_str(&event.data)
+            .map_err(|e| Error::Stream(format!("Failed to parse stream event: {}", e)))?;
+        
+        let text: String = chunk.candidates
+            .into_iter()
+            .next()
+            .and_then(|candidate| candidate.content)
+            .map(|content| content.parts.into_iter().filter_map(|part| part.text).collect())
+            .unwrap_or_default();
+        
+        // Every event repeats the usage so far
+        Ok((!text.is_empty()).then_some(StreamEvent::Delta(text))
+            .into_iter()
+            .chain(chunk.usage_metadata.map(|usage| StreamEvent::Usage(usage.into())))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl LLMProvider for GeminiProvider {
+    fn name(&self) -> &str {
+        "Gemini"
+    }
+    
+    fn default_base_url(&self) -> &str {
+        DEFAULT_BASE_URL
+    }
+    
+    fn default_embedding_model(&self) -> Option<&str> {
+        Some(DEFAULT_EMBEDDING_MODEL)
+    }
+    
+    fn max_embedding_batch(&self) -> usize {
+        MAX_EMBEDDING_BATCH
+    }
+    
+    async fn embed(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &EmbeddingRequest<'_>,
+    ) -> Result<Embeddings> {
+        let model = format!("models/{}", request.model);
+        let body = BatchEmbedRequest {
+            requests: request.inputs.iter()
+                .map(|text| EmbedContentRequest {
+                    model: model.clone(),
+                    content: Content {
+                        role: None,
+                        parts: vec![Part::text(text.clone())],
+                    },
+                    output_dimensionality: request.dimensions,
+                })
+                .collect(),
+        };
+        
+        let mut http_request = post(client, config, DEFAULT_BASE_URL, &format!("{}:batchEmbedContents", model));
+        if let Some(api_key) = &config.api_key {
+            http_request = http_request.header("x-goog-api-key", api_key);
+        }
+        
+        // The API reports no token usage for embeddings
+        let response: BatchEmbedResponse = serde_json::from_str(&send_request(config, http_request.json(&body)).await?)?;
+        Ok(Embeddings {
+            vectors: response.embeddings.into_iter().map(|embedding| embedding.values).collect(),
+            usage: None,
+        })
+    }
+    
+    async fn send(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+    ) -> Result<Completion> {
+        let body = send_request(config, self.build_request(client, config, request, false)).await?;
+        
+        let response: GenerateContentResponse = serde_json::from_str(&body)?;
+        let candidate = response.candidates
+            .into_iter()
+            .next()
+            .ok_or(Error::EmptyResponse)?;
+        
+        // Gemini calls have no IDs, so number them for the tool results
+        let mut reply = Message::assistant(String::new());
+        for part in candidate.content.map(|content| content.parts).unwrap_or_default() {
+            if let Some(text) = part.text {
+                reply.content.push_str(&text);
+            }
+            if let Some(call) = part.function_call {
+                reply.tool_calls.push(ToolCall {
+                    id: format!("call_{}", reply.tool_calls.len()),
+                    name: call.name,
+                    arguments: call.args,
+                });
+            }
+        }
+        
+        if reply.content.is_empty() && reply.tool_calls.is_empty() {
+            return Err(Error::EmptyResponse);
+        }
+        
+        // Gemini reports STOP even when the reply is a function call
+        let finish_reason = match candidate.finish_reason {
+            _ if !reply.tool_calls.is_empty() => Some(FinishReason::ToolCalls),
+            reason => reason.map(|reason| FinishReason::from_provider(&reason.to_lowercase())),
+        };
+        
+        Ok(Completion {
+            id: response.response_id,
+            model: response.model_version,
+            message: reply,
+            finish_reason,
+            usage: response.usage_metadata.map(Into::into),
+            cost: None,
+        })
+    }
+    
+    async fn stream(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+    ) -> Result<TokenStream> {
+        let response = send_checked(config, self.build_request(client, config, request, true)).await?;
+        Ok(sse::token_stream(response, Self::parse_event))
+    }
+}
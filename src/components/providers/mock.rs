@@ -0,0 +1,372 @@
+use super::LLMProvider;
+use crate::components::llm_interface::{
+    ChatRequest, Completion, EmbeddingRequest, Embeddings, FinishReason, LLMConfig, Message,
+    ResponseSchema, Role, TokenStream, Tool, ToolCall, Usage,
+};
+use crate::components::tokenizer::Tokenizer;
+use crate::{Error, Result};
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use regex::Regex;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Embedding size when the request does not reduce it
+const DEFAULT_EMBEDDING_DIMENSIONS: usize = 64;
+
+/// Failure the mock returns instead of a reply
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockError {
+    /// 429 with an optional retry hint
+    RateLimited { retry_after: Option<Duration> },
+    /// 500 internal server error
+    ServerError,
+    /// Any other status code and message
+    Status { status: u16, message: String },
+    /// The request timed out after the configured latency
+    Timeout,
+}
+
+impl From<MockError> for Error {
+    fn from(error: MockError) -> Self {
+        match error {
+            MockError::RateLimited { retry_after } => Error::RateLimited {
+                retry_after,
+                message: "Rate limit exceeded".to_string(),
+            },
+            MockError::ServerError => Error::Http {
+                status: 500,
+                body: "Internal server error".to_string(),
+            },
+            MockError::Status { status, message } => Error::Http { status, body: message },
+            MockError::Timeout => Error::Timeout,
+        }
+    }
+}
+
+/// Scripted outcome of a mocked call
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockResponse {
+    /// Plain assistant text
+    Text(String),
+    /// Assistant reply that calls tools
+    ToolCalls(Vec<ToolCall>),
+    /// Failure returned as the matching `Error`
+    Error(MockError),
+}
+
+impl MockResponse {
+    /// Create a reply that calls a single tool
+    pub fn tool_call(name: impl Into<String>, arguments: serde_json::Value) -> Self {
+        let name = name.into();
+        MockResponse::ToolCalls(vec![ToolCall {
+            id: format!("call_{}", name),
+            name,
+            arguments,
+        }])
+    }
+}
+
+/// Request received by the mock
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub model: String,
+    pub messages: Vec<Message>,
+    pub tools: Vec<Tool>,
+    pub response_schema: Option<ResponseSchema>,
+    pub stream: bool,
+}
+
+impl RecordedRequest {
+    /// Get the content of the last user message
+    pub fn last_user_message(&self) -> Option<&str> {
+        self.messages.iter()
+            .rev()
+            .find(|m| m.role == Role::User)
+            .map(|m| m.content.as_str())
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    rules: Vec<(Regex, MockResponse)>,
+    queue: VecDeque<MockResponse>,
+    fallback: Option<MockResponse>,
+    latency: Duration,
+    chunk_delay: Duration,
+    requests: Vec<RecordedRequest>,
+    embedding_batch: Option<usize>,
+    embedding_batches: Vec<usize>,
+}
+
+/// In-process provider that replays a script instead of calling an API
+/// 
+/// Replies are chosen in this order: the first rule whose pattern matches
+/// the last user message, then the next queued response, then the
+/// default reply. Requests matching nothing fail with `Error::Mock`.
+/// Clones share the script and the request log, so keep one to make
+/// assertions after handing another to `LLMInterface::with_provider`.
+/// 
+/// Injected errors are returned as they are, without retries.
+/// 
+/// Embeddings are hashed bags of words, so texts sharing words are
+/// close without any script.
+#[derive(Debug, Clone, Default)]
+pub struct MockProvider {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockProvider {
+    /// Create a mock with an empty script
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Queue a text reply
+    pub fn reply(self, text: impl Into<String>) -> Self {
+        self.enqueue(MockResponse::Text(text.into()));
+        self
+    }
+    
+    /// Queue any scripted response
+    pub fn respond(self, response: MockResponse) -> Self {
+        self.enqueue(response);
+        self
+    }
+    
+    /// Queue an injected error
+    pub fn fail(self, error: MockError) -> Self {
+        self.enqueue(MockResponse::Error(error));
+        self
+    }
+    
+    /// Reply with `text` whenever the last user message matches `pattern`
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `pattern` is not a valid regular expression.
+    pub fn when(self, pattern: &str, text: impl Into<String>) -> Self {
+        self.when_respond(pattern, MockResponse::Text(text.into()))
+    }
+    
+    /// Use `response` whenever the last user message matches `pattern`
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `pattern` is not a valid regular expression.
+    pub fn when_respond(self, pattern: &str, response: MockResponse) -> Self {
+        let regex = Regex::new(pattern).expect("mock rule pattern must be a valid regex");
+        self.state.lock().unwrap().rules.push((regex, response));
+        self
+    }
+    
+    /// Reply with `text` when no rule or queued response applies
+    pub fn default_reply(self, text: impl Into<String>) -> Self {
+        self.state.lock().unwrap().fallback = Some(MockResponse::Text(text.into()));
+        self
+    }
+    
+    /// Wait before every reply or error
+    pub fn latency(self, latency: Duration) -> Self {
+        self.state.lock().unwrap().latency = latency;
+        self
+    }
+    
+    /// Wait between streamed chunks
+    pub fn chunk_delay(self, delay: Duration) -> Self {
+        self.state.lock().unwrap().chunk_delay = delay;
+        self
+    }
+    
+    /// Limit how many texts one embedding request may carry
+    pub fn embedding_batch(self, size: usize) -> Self {
+        self.state.lock().unwrap().embedding_batch = Some(size);
+        self
+    }
+    
+    /// Queue a response on a mock already handed to an interface
+    pub fn enqueue(&self, response: MockResponse) {
+        self.state.lock().unwrap().queue.push_back(response);
+    }
+    
+    /// Get every request received so far
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+    
+    /// Get the most recent request
+    pub fn last_request(&self) -> Option<RecordedRequest> {
+        self.state.lock().unwrap().requests.last().cloned()
+    }
+    
+    /// Get the number of requests received
+    pub fn request_count(&self) -> usize {
+        self.state.lock().unwrap().requests.len()
+    }
+    
+    /// Get the number of texts in each embedding request received so far
+    pub fn embedding_batches(&self) -> Vec<usize> {
+        self.state.lock().unwrap().embedding_batches.clone()
+    }
+    
+    /// Record a request and pick its scripted response
+    async fn respond_to(
+        &self,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+        stream: bool,
+    ) -> Result<MockResponse> {
+        let recorded = RecordedRequest {
+            model: config.model.clone(),
+            messages: request.messages.to_vec(),
+            tools: request.tools.to_vec(),
+            response_schema: request.response_schema.cloned(),
+            stream,
+        };
+        let prompt = recorded.last_user_message().unwrap_or_default().to_string();
+        
+        let (response, latency) = {
+            let mut state = self.state.lock().unwrap();
+            state.requests.push(recorded);
+            
+            let rule = state.rules.iter()
+                .find(|(pattern, _)| pattern.is_match(&prompt))
+                .map(|(_, response)| response.clone());
+            let response = rule
+                .or_else(|| state.queue.pop_front())
+                .or_else(|| state.fallback.clone());
+            (response, state.latency)
+        };
+        
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+        
+        response.ok_or_else(|| Error::Mock(format!("no scripted response for prompt: {}", prompt)))
+    }
+}
+
+#[async_trait]
+impl LLMProvider for MockProvider {
+    fn name(&self) -> &str {
+        "Mock"
+    }
+    
+    fn default_base_url(&self) -> &str {
+        "mock://"
+    }
+    
+    fn is_self_hosted(&self) -> bool {
+        true
+    }
+    
+    fn default_embedding_model(&self) -> Option<&str> {
+        Some("mock-embedding")
+    }
+    
+    fn max_embedding_batch(&self) -> usize {
+        self.state.lock().unwrap().embedding_batch.unwrap_or(usize::MAX)
+    }
+    
+    async fn embed(
+        &self,
+        _client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &EmbeddingRequest<'_>,
+    ) -> Result<Embeddings> {
+        self.state.lock().unwrap().embedding_batches.push(request.inputs.len());
+        
+        let dimensions = request.dimensions.map_or(DEFAULT_EMBEDDING_DIMENSIONS, |d| d as usize).max(1);
+        let vectors = request.inputs.iter().map(|text| hashed_embedding(text, dimensions)).collect();
+        let tokenizer = Tokenizer::for_model(&config.model);
+        let tokens: usize = request.inputs.iter().map(|text| tokenizer.count_tokens(text)).sum();
+        
+        Ok(Embeddings {
+            vectors,
+            usage: Some(Usage {
+                prompt_tokens: tokens as u32,
+                total_tokens: tokens as u32,
+                ..Usage::default()
+            }),
+        })
+    }
+    
+    async fn send(
+        &self,
+        _client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+    ) -> Result<Completion> {
+        let (message, finish_reason) = match self.respond_to(config, request, false).await? {
+            MockResponse::ToolCalls(calls) => {
+                let mut message = Message::assistant(String::new());
+                message.tool_calls = calls;
+                (message, FinishReason::ToolCalls)
+            }
+            MockResponse::Text(text) => (Message::assistant(text), FinishReason::Stop),
+            MockResponse::Error(error) => return Err(error.into()),
+        };
+        
+        // Token counts come from the offline tokenizer so they are deterministic
+        let tokenizer = Tokenizer::for_model(&config.model);
+        let prompt_tokens = tokenizer.count_conversation_tokens(request.messages) as u32;
+        let completion_tokens = tokenizer.count_message_tokens(&message) as u32;
+        
+        Ok(Completion {
+            id: Some(format!("mock-{}", self.request_count())),
+            model: Some(config.model.clone()),
+            message,
+            finish_reason: Some(finish_reason),
+            usage: Some(Usage {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens: prompt_tokens + completion_tokens,
+                cached_tokens: 0,
+            }),
+            cost: None,
+        })
+    }
+    
+    async fn stream(
+        &self,
+        _client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+    ) -> Result<TokenStream> {
+        let text = match self.respond_to(config, request, true).await? {
+            MockResponse::Text(text) => text,
+            MockResponse::Error(error) => return Err(error.into()),
+            MockResponse::ToolCalls(_) => return Err(Error::Mock("only text replies can be streamed".to_string())),
+        };
+        
+        // Stream word by word, keeping the whitespace with each word
+        let chunks: Vec<String> = text.split_inclusive(char::is_whitespace).map(String::from).collect();
+        let delay = self.state.lock().unwrap().chunk_delay;
+        
+        Ok(TokenStream::new(stream::iter(chunks).then(move |chunk| async move {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            Ok(chunk)
+        })))
+    }
+}
+
+/// Hash each lowercased word into a bucket and normalize the counts
+fn hashed_embedding(text: &str, dimensions: usize) -> Vec<f32> {
+    let mut vector = vec![0.0f32; dimensions];
+    for word in text.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()) {
+        // FNV-1a keeps buckets stable across runs and platforms
+        let hash = word.to_lowercase().bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+        vector[(hash % dimensions as u64) as usize] += 1.0;
+    }
+    
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+    vector
+}
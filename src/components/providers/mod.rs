@@ -0,0 +1,310 @@
+//! LLM provider backends
+//! 
+//! This module contains the provider abstraction used by `LLMInterface`
+//! and the built-in backends:
+//! - OpenAI chat completions
+//! - Anthropic Messages API
+//! - Azure OpenAI deployments
+//! - Google Gemini generateContent
+//! - Ollama native API
+//! - Scriptable mock for offline tests
+
+pub mod anthropic;
+pub mod azure;
+pub mod gemini;
+pub mod mock;
+pub(crate) mod ndjson;
+pub mod ollama;
+pub mod openai;
+pub(crate) mod sse;
+
+pub use anthropic::AnthropicProvider;
+pub use azure::{AzureOpenAIProvider, AzureOptions};
+pub use gemini::GeminiProvider;
+pub use mock::{MockError, MockProvider, MockResponse, RecordedRequest};
+pub use ollama::{OllamaEndpoint, OllamaOptions, OllamaProvider};
+pub use openai::OpenAIProvider;
+
+use crate::components::llm_interface::{
+    ChatRequest, Completion, EmbeddingRequest, Embeddings, LLMConfig, TokenStream,
+};
+use crate::components::retry::{self, RetryPolicy};
+use crate::{Error, Result};
+use async_trait::async_trait;
+use futures::stream;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use tracing::{debug, warn};
+
+/// Backend that knows how to talk to a specific LLM API
+/// 
+/// Providers translate the provider-neutral request into their own
+/// wire format, send it and convert the reply back into a `Completion`.
+#[async_trait]
+pub trait LLMProvider: Debug + Send + Sync {
+    /// Get the provider name
+    fn name(&self) -> &str;
+    
+    /// Get the API root used when the config has no base URL
+    fn default_base_url(&self) -> &str;
+    
+    /// Check if the backend runs without credentials
+    /// 
+    /// Self-hosted backends need no API key and may serve models that
+    /// are not in the model catalog.
+    fn is_self_hosted(&self) -> bool {
+        false
+    }
+    
+    /// Check that the backend can serve the configured model
+    /// 
+    /// Called by `LLMInterface::verify`. Hosted APIs are not contacted.
+    async fn verify(&self, _client: &reqwest::Client, _config: &LLMConfig) -> Result<()> {
+        Ok(())
+    }
+    
+    /// List the models the backend can serve
+    async fn list_models(&self, _client: &reqwest::Client, _config: &LLMConfig) -> Result<Vec<String>> {
+        Err(Error::Unsupported {
+            provider: self.name().to_string(),
+            operation: "model listing".to_string(),
+        })
+    }
+    
+    /// Send the request and return the assistant reply with its metadata
+    async fn send(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+    ) -> Result<Completion>;
+    
+    /// Get the embedding model used when the config does not name one
+    fn default_embedding_model(&self) -> Option<&str> {
+        None
+    }
+    
+    /// Get the most texts the backend embeds in one request
+    fn max_embedding_batch(&self) -> usize {
+        1
+    }
+    
+    /// Embed a batch of texts
+    async fn embed(
+        &self,
+        _client: &reqwest::Client,
+        _config: &LLMConfig,
+        _request: &EmbeddingRequest<'_>,
+    ) -> Result<Embeddings> {
+        Err(Error::Unsupported {
+            provider: self.name().to_string(),
+            operation: "embeddings".to_string(),
+        })
+    }
+    
+    /// Send the request and stream the assistant text as it is generated
+    /// 
+    /// Providers without streaming support fall back to a single delta
+    /// containing the full reply.
+    async fn stream(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+    ) -> Result<TokenStream> {
+        let completion = self.send(client, config, request).await?;
+        Ok(TokenStream::new(stream::once(async move { Ok(completion.message.content) })))
+    }
+}
+
+/// Built-in provider selection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    #[default]
+    OpenAI,
+    Anthropic,
+    Azure,
+    Gemini,
+    Ollama,
+}
+
+impl ProviderKind {
+    /// Guess the provider from a model name
+    pub fn from_model(model: &str) -> Self {
+        if model.starts_with("claude") {
+            ProviderKind::Anthropic
+        } else if model.starts_with("gemini") {
+            ProviderKind::Gemini
+        } else {
+            ProviderKind::OpenAI
+        }
+    }
+    
+    /// Create the backend for this provider
+    pub fn create(self) -> Box<dyn LLMProvider> {
+        match self {
+            ProviderKind::OpenAI => Box::new(OpenAIProvider),
+            ProviderKind::Anthropic => Box::new(AnthropicProvider),
+            ProviderKind::Azure => Box::new(AzureOpenAIProvider),
+            ProviderKind::Gemini => Box::new(GeminiProvider),
+            ProviderKind::Ollama => Box::new(OllamaProvider::chat()),
+        }
+    }
+}
+
+/// Error body returned by provider APIs on failure
+/// 
+/// Both OpenAI and Anthropic nest a `message` inside an `error` object;
+/// OpenAI also sets a machine-readable `code`. Ollama sends the message
+/// as a plain string.
+#[derive(Debug, Deserialize)]
+struct APIErrorResponse {
+    error: APIErrorBody,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum APIErrorBody {
+    Object(APIError),
+    Message(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct APIError {
+    message: String,
+    #[serde(default)]
+    code: Option<serde_json::Value>,
+}
+
+/// Start a POST request to `path` under the configured base URL
+/// 
+/// Applies the extra headers from the config so every provider honors them.
+pub(crate) fn post(
+    client: &reqwest::Client,
+    config: &LLMConfig,
+    default_base_url: &str,
+    path: &str,
+) -> reqwest::RequestBuilder {
+    with_headers(client.post(endpoint_url(config, default_base_url, path)), config)
+}
+
+/// Start a GET request to `path` under the configured base URL
+pub(crate) fn get(
+    client: &reqwest::Client,
+    config: &LLMConfig,
+    default_base_url: &str,
+    path: &str,
+) -> reqwest::RequestBuilder {
+    with_headers(client.get(endpoint_url(config, default_base_url, path)), config)
+}
+
+/// Join `path` onto the configured base URL
+fn endpoint_url(config: &LLMConfig, default_base_url: &str, path: &str) -> String {
+    let base_url = config.base_url.as_deref().unwrap_or(default_base_url);
+    format!("{}/{}", base_url.trim_end_matches('/'), path.trim_start_matches('/'))
+}
+
+/// Apply the extra headers from the config
+fn with_headers(request: reqwest::RequestBuilder, config: &LLMConfig) -> reqwest::RequestBuilder {
+    config.headers
+        .iter()
+        .fold(request, |request, (name, value)| request.header(name, value))
+}
+
+/// Send a prepared request and return the response if it succeeded
+/// 
+/// Transient failures are retried according to the config's retry
+/// policy. Other non-success statuses are turned into errors carrying
+/// the status and the API's error message.
+pub(crate) async fn send_checked(
+    config: &LLMConfig,
+    request: reqwest::RequestBuilder,
+) -> Result<reqwest::Response> {
+    let policy = &config.retry;
+    let mut request = request;
+    let mut attempt = 1;
+    
+    loop {
+        // JSON bodies can always be cloned; keep a copy for the next attempt
+        let next = request.try_clone();
+        let can_retry = next.is_some() && attempt < policy.max_attempts;
+        
+        let delay = match execute(config, request).await {
+            Ok(response) => {
+                let status = response.status();
+                debug!("API responded with status {}", status);
+                
+                if let Some(limiter) = &config.rate_limiter {
+                    limiter.update_from_headers(response.headers());
+                }
+                
+                if status.is_success() {
+                    return Ok(response);
+                }
+                if !can_retry || !RetryPolicy::is_retryable_status(status) {
+                    return Err(api_error(response).await);
+                }
+                
+                let delay = policy.delay(attempt, Some(response.headers()));
+                warn!("API returned {}; retrying in {:?} (attempt {})", status, delay, attempt);
+                delay
+            }
+            Err(Error::Request(e)) if can_retry && RetryPolicy::is_retryable_error(&e) => {
+                let delay = policy.delay(attempt, None);
+                warn!("Request failed: {}; retrying in {:?} (attempt {})", e, delay, attempt);
+                delay
+            }
+            Err(Error::Request(e)) if e.is_timeout() => return Err(Error::Timeout),
+            Err(e) => return Err(e),
+        };
+        
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+        request = next.expect("retries only happen when the request was cloned");
+    }
+}
+
+/// Send a single attempt, through the cassette when one is set
+async fn execute(config: &LLMConfig, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+    match &config.cassette {
+        Some(cassette) => cassette.execute(request).await,
+        None => Ok(request.send().await?),
+    }
+}
+
+/// Build a typed error from a failed response
+async fn api_error(response: reqwest::Response) -> Error {
+    let status = response.status().as_u16();
+    let retry_after = retry::server_delay(response.headers());
+    let body = match response.text().await {
+        Ok(body) => body,
+        Err(e) => return Error::Request(e),
+    };
+    
+    // Prefer the structured error message when the API provides one
+    let (message, code) = match serde_json::from_str::<APIErrorResponse>(&body) {
+        Ok(APIErrorResponse { error: APIErrorBody::Object(e) }) => (e.message, e.code),
+        Ok(APIErrorResponse { error: APIErrorBody::Message(message) }) => (message, None),
+        Err(_) => (body, None),
+    };
+    
+    let is_context_error = code.as_ref().and_then(|c| c.as_str()) == Some("context_length_exceeded")
+        || message.contains("maximum context length")
+        || message.contains("prompt is too long");
+    
+    match status {
+        401 | 403 => Error::Authentication { status, message },
+        429 => Error::RateLimited { retry_after, message },
+        400 | 413 if is_context_error => Error::ContextLengthExceeded { message },
+        _ => Error::Http { status, body: message },
+    }
+}
+
+/// Send a prepared request and return the response body
+pub(crate) async fn send_request(
+    config: &LLMConfig,
+    request: reqwest::RequestBuilder,
+) -> Result<String> {
+    Ok(send_checked(config, request).await?.text().await?)
+}
@@ -0,0 +1,96 @@
+use super::sse::{self, StreamEvent, UsageSlot};
+use crate::components::llm_interface::TokenStream;
+use crate::{Error, Result};
+use futures::stream::{self, StreamExt};
+use std::collections::VecDeque;
+
+/// Incremental splitter for newline-delimited JSON
+/// 
+/// Bytes are buffered until a full line is available so multi-byte
+/// characters split across network chunks are decoded correctly.
+#[derive(Debug, Default)]
+struct LineParser {
+    buffer: Vec<u8>,
+}
+
+impl LineParser {
+    /// Feed a chunk of bytes and return the non-empty lines it completed
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut lines = Vec::new();
+        
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim();
+            if !line.is_empty() {
+                lines.push(line.to_string());
+            }
+        }
+        
+        lines
+    }
+    
+    /// Flush a trailing line that was not followed by a newline
+    fn finish(&mut self) -> Option<String> {
+        let line = String::from_utf8_lossy(&std::mem::take(&mut self.buffer)).trim().to_string();
+        (!line.is_empty()).then_some(line)
+    }
+}
+
+/// Turn an NDJSON response into a stream of text deltas
+/// 
+/// `parse` maps each line to what it carries; the stream ends at the
+/// first `Done` line, error, or when the connection closes. Reported
+/// usage is available from the stream.
+pub(crate) fn token_stream(
+    response: reqwest::Response,
+    parse: fn(&str) -> Result<Vec<StreamEvent>>,
+) -> TokenStream {
+    let usage = UsageSlot::default();
+    let state = (
+        response.bytes_stream(),
+        LineParser::default(),
+        VecDeque::<String>::new(),
+        VecDeque::<String>::new(),
+        false,
+        usage.clone(),
+    );
+    
+    let deltas = stream::unfold(state, move |(mut bytes, mut parser, mut lines, mut pending, mut finished, usage)| async move {
+        loop {
+            if let Some(text) = pending.pop_front() {
+                return Some((Ok(text), (bytes, parser, lines, pending, finished, usage)));
+            }
+            if let Some(line) = lines.pop_front() {
+                match parse(&line) {
+                    Ok(parsed) => {
+                        if sse::apply(parsed, &mut pending, &usage) {
+                            lines.clear();
+                            finished = true;
+                        }
+                        continue;
+                    }
+                    Err(e) => return Some((Err(e), (bytes, parser, VecDeque::new(), VecDeque::new(), true, usage))),
+                }
+            }
+            if finished {
+                return None;
+            }
+            
+            match bytes.next().await {
+                Some(Ok(chunk)) => lines.extend(parser.push(&chunk)),
+                Some(Err(e)) => {
+                    let state = (bytes, parser, VecDeque::new(), VecDeque::new(), true, usage);
+                    return Some((Err(Error::Request(e)), state));
+                }
+                None => {
+                    finished = true;
+                    lines.extend(parser.finish());
+                }
+            }
+        }
+    });
+    
+    TokenStream::with_usage(deltas, usage)
+}
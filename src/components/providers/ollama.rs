@@ -0,0 +1,512 @@
+use super::sse::StreamEvent;
+use super::{get, ndjson, post, send_checked, send_request, LLMProvider};
+use crate::components::llm_interface::{
+    ChatRequest, Completion, EmbeddingRequest, Embeddings, FinishReason, LLMConfig, Message, Role,
+    TokenStream, Tool, ToolCall, Usage,
+};
+use crate::{Error, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// Default API root for a local Ollama server
+const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+
+/// Tag Ollama gives models pulled without an explicit one
+const DEFAULT_TAG: &str = "latest";
+
+/// Embedding model used when the config does not name one
+const DEFAULT_EMBEDDING_MODEL: &str = "nomic-embed-text";
+
+/// Texts per embedding request; Ollama has no hard limit, but a local
+/// server embeds a whole request before answering
+const MAX_EMBEDDING_BATCH: usize = 256;
+
+/// Ollama model options beyond the common config fields
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct OllamaOptions {
+    /// Context window to load the model with; Ollama defaults to 2048 or 4096
+    pub num_ctx: Option<u32>,
+    /// Sampling seed for reproducible replies
+    pub seed: Option<u64>,
+    /// Sample only from the `top_k` most likely tokens
+    pub top_k: Option<u32>,
+}
+
+/// Which native endpoint requests are sent to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OllamaEndpoint {
+    /// `/api/chat` with the full message history and tool calling
+    #[default]
+    Chat,
+    /// `/api/generate` with the history flattened into a single prompt
+    Generate,
+}
+
+/// `options` object of a request
+#[derive(Debug, Default, Serialize)]
+struct RequestOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_predict: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_ctx: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+}
+
+impl RequestOptions {
+    fn new(config: &LLMConfig) -> Self {
+        Self {
+            temperature: config.temperature,
+            num_predict: config.max_tokens,
+            num_ctx: config.ollama.num_ctx,
+            seed: config.ollama.seed,
+            top_k: config.ollama.top_k,
+        }
+    }
+}
+
+/// Request structure for `/api/chat`
+#[derive(Debug, Serialize)]
+struct OllamaChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<OllamaMessage>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<OllamaTool<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'a serde_json::Value>,
+    options: RequestOptions,
+}
+
+/// Request structure for `/api/generate`
+#[derive(Debug, Serialize)]
+struct GenerateRequest<'a> {
+    model: &'a str,
+    prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'a serde_json::Value>,
+    options: RequestOptions,
+}
+
+/// Message in the native chat format
+#[derive(Debug, Serialize, Deserialize)]
+struct OllamaMessage {
+    role: Role,
+    #[serde(default)]
+    content: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tool_calls: Vec<OllamaToolCall>,
+    /// Base64-encoded images
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    images: Vec<String>,
+}
+
+/// Tool call in the native format, which has no call IDs
+#[derive(Debug, Serialize, Deserialize)]
+struct OllamaToolCall {
+    function: OllamaFunction,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OllamaFunction {
+    name: String,
+    arguments: serde_json::Value,
+}
+
+/// Tool definition in the native format
+#[derive(Debug, Serialize)]
+struct OllamaTool<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    function: OllamaToolFunction<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaToolFunction<'a> {
+    name: &'a str,
+    description: &'a str,
+    parameters: &'a serde_json::Value,
+}
+
+impl<'a> From<&'a Tool> for OllamaTool<'a> {
+    fn from(tool: &'a Tool) -> Self {
+        Self {
+            kind: "function",
+            function: OllamaToolFunction {
+                name: &tool.name,
+                description: &tool.description,
+                parameters: &tool.parameters,
+            },
+        }
+    }
+}
+
+/// Response from `/api/chat` or `/api/generate`, or one line of their streams
+/// 
+/// Chat replies carry a `message`, generate replies a `response` string.
+#[derive(Debug, Deserialize)]
+struct OllamaResponse {
+    model: Option<String>,
+    #[serde(default)]
+    message: Option<OllamaMessage>,
+    #[serde(default)]
+    response: Option<String>,
+    #[serde(default)]
+    done: bool,
+    done_reason: Option<String>,
+    prompt_eval_count: Option<u32>,
+    eval_count: Option<u32>,
+    error: Option<String>,
+}
+
+/// Request structure for `/api/embed`
+#[derive(Debug, Serialize)]
+struct EmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dimensions: Option<u32>,
+    options: RequestOptions,
+}
+
+/// Response from `/api/embed`
+#[derive(Debug, Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+    prompt_eval_count: Option<u32>,
+}
+
+/// Response from `/api/tags`
+#[derive(Debug, Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<TagsModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsModel {
+    name: String,
+}
+
+/// Native Ollama API backend
+/// 
+/// Talks to `/api/chat` by default, or to `/api/generate` when created
+/// with `OllamaProvider::generate`. Streams arrive as newline-delimited
+/// JSON. `initialize` checks that the server is up and the model has
+/// been pulled.
+#[derive(Debug, Clone, Default)]
+pub struct OllamaProvider {
+    endpoint: OllamaEndpoint,
+}
+
+impl OllamaProvider {
+    /// Create a backend for `/api/chat`
+    pub fn chat() -> Self {
+        Self { endpoint: OllamaEndpoint::Chat }
+    }
+    
+    /// Create a backend for `/api/generate`
+    pub fn generate() -> Self {
+        Self { endpoint: OllamaEndpoint::Generate }
+    }
+    
+    /// Get the endpoint requests are sent to
+    pub fn endpoint(&self) -> OllamaEndpoint {
+        self.endpoint
+    }
+    
+    /// Convert messages to the native chat format
+    /// 
+    /// Ollama only takes inline image data, so image URLs are rejected.
+    fn convert_messages(&self, messages: &[Message]) -> Result<Vec<OllamaMessage>> {
+        messages.iter()
+            .map(|message| {
+                let images = message.images.iter()
+                    .map(|image| match image.inline_data() {
+                        Some((_, data)) => Ok(data.to_string()),
+                        None => Err(Error::Unsupported {
+                            provider: self.name().to_string(),
+                            operation: "image URLs".to_string(),
+                        }),
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(OllamaMessage {
+                    role: message.role,
+                    content: message.content.clone(),
+                    tool_calls: message.tool_calls.iter()
+                        .map(|call| OllamaToolCall {
+                            function: OllamaFunction {
+                                name: call.name.clone(),
+                                arguments: call.arguments.clone(),
+                            },
+                        })
+                        .collect(),
+                    images,
+                })
+            })
+            .collect()
+    }
+    
+    /// Split messages into a system prompt and a single generate prompt
+    /// 
+    /// A lone user message is sent as it is; longer histories are written
+    /// out as a transcript.
+    fn flatten_messages(messages: &[Message]) -> (Option<String>, String) {
+        let system: Vec<&str> = messages.iter()
+            .filter(|m| m.role == Role::System)
+            .map(|m| m.content.as_str())
+            .collect();
+        let rest: Vec<&Message> = messages.iter().filter(|m| m.role != Role::System).collect();
+        
+        let prompt = match rest.as_slice() {
+            [message] if message.role == Role::User => message.content.clone(),
+            _ => rest.iter()
+                .map(|m| match m.role {
+                    Role::Assistant => format!("Assistant: {}", m.content),
+                    Role::Tool => format!("Tool result: {}", m.content),
+                    _ => format!("User: {}", m.content),
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        };
+        
+        let system = (!system.is_empty()).then(|| system.join("\n\n"));
+        (system, prompt)
+    }
+    
+    /// Build the HTTP request for the configured endpoint
+    fn build_request(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+        stream: bool,
+    ) -> Result<reqwest::RequestBuilder> {
+        let format = request.response_schema.map(|schema| &schema.schema);
+        
+        let http_request = match self.endpoint {
+            OllamaEndpoint::Chat => {
+                let body = OllamaChatRequest {
+                    model: &config.model,
+                    messages: self.convert_messages(request.messages)?,
+                    stream,
+                    tools: request.tools.iter().map(Into::into).collect(),
+                    format,
+                    options: RequestOptions::new(config),
+                };
+                post(client, config, DEFAULT_BASE_URL, "api/chat").json(&body)
+            }
+            OllamaEndpoint::Generate => {
+                if !request.tools.is_empty() {
+                    return Err(Error::Unsupported {
+                        provider: self.name().to_string(),
+                        operation: "tool calling on /api/generate".to_string(),
+                    });
+                }
+                if request.messages.iter().any(|message| !message.images.is_empty()) {
+                    return Err(Error::Unsupported {
+                        provider: self.name().to_string(),
+                        operation: "images on /api/generate".to_string(),
+                    });
+                }
+                
+                let (system, prompt) = Self::flatten_messages(request.messages);
+                let body = GenerateRequest {
+                    model: &config.model,
+                    prompt,
+                    system,
+                    stream,
+                    format,
+                    options: RequestOptions::new(config),
+                };
+                post(client, config, DEFAULT_BASE_URL, "api/generate").json(&body)
+            }
+        };
+        
+        Ok(Self::authorize(http_request, config))
+    }
+    
+    /// Add the API key, if any
+    /// 
+    /// Ollama itself has no auth, but proxies in front of it may.
+    fn authorize(http_request: reqwest::RequestBuilder, config: &LLMConfig) -> reqwest::RequestBuilder {
+        match &config.api_key {
+            Some(api_key) => http_request.bearer_auth(api_key),
+            None => http_request,
+        }
+    }
+    
+    /// Extract the text delta from a streamed line
+    fn parse_line(line: &str) -> Result<Vec<StreamEvent>> {
+        let chunk: OllamaResponse = serde_json::from_str(line)
+            .map_err(|e| Error::Stream(format!("Failed to parse stream line: {}", e)))?;
+        
+        if let Some(error) = chunk.error {
+            return Err(Error::Stream(error));
+        }
+        
+        let mut events = Vec::new();
+        let usage = Self::usage(&chunk);
+        let text = chunk.message.map(|m| m.content).or(chunk.response).unwrap_or_default();
+        if !text.is_empty() {
+            events.push(StreamEvent::Delta(text));
+        }
+        // The final line carries the token counts
+        if chunk.done {
+            events.extend(usage.map(StreamEvent::Usage));
+            events.push(StreamEvent::Done);
+        }
+        Ok(events)
+    }
+    
+    /// Token counts reported with a finished reply
+    fn usage(response: &OllamaResponse) -> Option<Usage> {
+        match (response.prompt_eval_count, response.eval_count) {
+            (None, None) => None,
+            (prompt_tokens, completion_tokens) => {
+                let prompt_tokens = prompt_tokens.unwrap_or(0);
+                let completion_tokens = completion_tokens.unwrap_or(0);
+                Some(Usage {
+                    prompt_tokens,
+                    completion_tokens,
+                    total_tokens: prompt_tokens + completion_tokens,
+                    cached_tokens: 0,
+                })
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl LLMProvider for OllamaProvider {
+    fn name(&self) -> &str {
+        "Ollama"
+    }
+    
+    fn default_base_url(&self) -> &str {
+        DEFAULT_BASE_URL
+    }
+    
+    fn is_self_hosted(&self) -> bool {
+        true
+    }
+    
+    async fn verify(&self, client: &reqwest::Client, config: &LLMConfig) -> Result<()> {
+        let models = self.list_models(client, config).await?;
+        
+        // `llama3.2` is pulled as `llama3.2:latest`
+        let tagged = format!("{}:{}", config.model, DEFAULT_TAG);
+        if !models.iter().any(|name| *name == config.model || *name == tagged) {
+            return Err(Error::ModelNotPulled(config.model.clone()));
+        }
+        
+        info!("Ollama server is serving model {}", config.model);
+        Ok(())
+    }
+    
+    async fn list_models(&self, client: &reqwest::Client, config: &LLMConfig) -> Result<Vec<String>> {
+        let body = send_request(config, Self::authorize(get(client, config, DEFAULT_BASE_URL, "api/tags"), config)).await?;
+        let tags: TagsResponse = serde_json::from_str(&body)?;
+        Ok(tags.models.into_iter().map(|model| model.name).collect())
+    }
+    
+    fn default_embedding_model(&self) -> Option<&str> {
+        Some(DEFAULT_EMBEDDING_MODEL)
+    }
+    
+    fn max_embedding_batch(&self) -> usize {
+        MAX_EMBEDDING_BATCH
+    }
+    
+    async fn embed(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &EmbeddingRequest<'_>,
+    ) -> Result<Embeddings> {
+        let body = EmbedRequest {
+            model: request.model,
+            input: request.inputs,
+            dimensions: request.dimensions,
+            options: RequestOptions {
+                num_ctx: config.ollama.num_ctx,
+                ..RequestOptions::default()
+            },
+        };
+        let http_request = Self::authorize(post(client, config, DEFAULT_BASE_URL, "api/embed"), config).json(&body);
+        
+        let response: EmbedResponse = serde_json::from_str(&send_request(config, http_request).await?)?;
+        Ok(Embeddings {
+            vectors: response.embeddings,
+            usage: response.prompt_eval_count.map(|tokens| Usage {
+                prompt_tokens: tokens,
+                total_tokens: tokens,
+                ..Usage::default()
+            }),
+        })
+    }
+    
+    async fn send(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+    ) -> Result<Completion> {
+        let body = send_request(config, self.build_request(client, config, request, false)?).await?;
+        
+        let response: OllamaResponse = serde_json::from_str(&body)?;
+        let usage = Self::usage(&response);
+        
+        let mut reply = Message::assistant(String::new());
+        if let Some(message) = response.message {
+            reply.content = message.content;
+            reply.tool_calls = message.tool_calls.into_iter()
+                .enumerate()
+                .map(|(index, call)| ToolCall {
+                    id: format!("call_{}", index),
+                    name: call.function.name,
+                    arguments: call.function.arguments,
+                })
+                .collect();
+        } else if let Some(text) = response.response {
+            reply.content = text;
+        }
+        
+        if reply.content.is_empty() && reply.tool_calls.is_empty() {
+            return Err(Error::EmptyResponse);
+        }
+        
+        // Ollama reports "stop" even when the reply is a tool call
+        let finish_reason = match response.done_reason.as_deref() {
+            _ if !reply.tool_calls.is_empty() => Some(FinishReason::ToolCalls),
+            reason => reason.map(FinishReason::from_provider),
+        };
+        
+        Ok(Completion {
+            id: None,
+            model: response.model,
+            message: reply,
+            finish_reason,
+            usage,
+            cost: None,
+        })
+    }
+    
+    async fn stream(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+    ) -> Result<TokenStream> {
+        let response = send_checked(config, self.build_request(client, config, request, true)?).await?;
+        Ok(ndjson::token_stream(response, Self::parse_line))
+    }
+}
@@ -0,0 +1,249 @@
+use super::sse::{self, SseEvent, StreamEvent};
+use super::{post, send_checked, send_request, LLMProvider};
+use crate::components::llm_interface::{
+    ChatRequest, Completion, EmbeddingRequest, Embeddings, FinishReason, LLMConfig, LLMRequest,
+    LLMResponse, LLMUsage, StreamOptions, TokenStream, Usage,
+};
+use crate::{Error, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Default API root for OpenAI
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Embedding model used when the config does not name one
+pub(super) const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+/// Most inputs the embeddings endpoint accepts in one request
+pub(super) const MAX_EMBEDDING_BATCH: usize = 2048;
+
+/// Streamed chunk of a chat completion
+#[derive(Debug, Deserialize)]
+struct ChunkResponse {
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+    /// Sent in a final chunk with no choices when usage is requested
+    usage: Option<LLMUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    delta: Delta,
+}
+
+#[derive(Debug, Deserialize)]
+struct Delta {
+    content: Option<String>,
+}
+
+/// Request structure for the embeddings endpoint
+#[derive(Debug, Serialize)]
+pub(super) struct EmbeddingsBody<'a> {
+    model: &'a str,
+    input: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dimensions: Option<u32>,
+    encoding_format: &'static str,
+}
+
+/// Response from the embeddings endpoint
+#[derive(Debug, Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+    usage: Option<EmbeddingsUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// Embeddings usage, which has no completion tokens
+#[derive(Debug, Deserialize)]
+struct EmbeddingsUsage {
+    prompt_tokens: u32,
+    total_tokens: u32,
+}
+
+impl From<EmbeddingsUsage> for Usage {
+    fn from(usage: EmbeddingsUsage) -> Self {
+        LLMUsage {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: 0,
+            total_tokens: usage.total_tokens,
+            prompt_tokens_details: None,
+        }
+        .into()
+    }
+}
+
+/// OpenAI chat completions backend
+#[derive(Debug, Clone, Default)]
+pub struct OpenAIProvider;
+
+impl OpenAIProvider {
+    /// Build the chat completions body, shared with Azure deployments
+    /// 
+    /// Reasoning models get `max_completion_tokens` instead of
+    /// `max_tokens`, and no temperature.
+    pub(super) fn request_body<'a>(
+        config: &'a LLMConfig,
+        request: &ChatRequest<'a>,
+        stream: bool,
+    ) -> LLMRequest<'a> {
+        let reasoning = request.reasoning;
+        LLMRequest {
+            model: &config.model,
+            messages: request.messages.iter().map(Into::into).collect(),
+            max_tokens: config.max_tokens.filter(|_| !reasoning),
+            max_completion_tokens: config.max_tokens.filter(|_| reasoning),
+            temperature: config.temperature.filter(|_| !reasoning),
+            stream: stream.then_some(true),
+            stream_options: stream.then_some(StreamOptions { include_usage: true }),
+            tools: request.tools.iter().map(Into::into).collect(),
+            response_format: request.response_schema.map(Into::into),
+        }
+    }
+    
+    /// Build the HTTP request for a chat completion
+    fn build_request(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+        stream: bool,
+    ) -> reqwest::RequestBuilder {
+        Self::authorize(post(client, config, DEFAULT_BASE_URL, "chat/completions"), config)
+            .json(&Self::request_body(config, request, stream))
+    }
+    
+    /// Add the API key and organization headers
+    fn authorize(mut http_request: reqwest::RequestBuilder, config: &LLMConfig) -> reqwest::RequestBuilder {
+        if let Some(api_key) = &config.api_key {
+            http_request = http_request.bearer_auth(api_key);
+        }
+        if let Some(organization) = &config.organization {
+            http_request = http_request.header("OpenAI-Organization", organization);
+        }
+        if let Some(project) = &config.project {
+            http_request = http_request.header("OpenAI-Project", project);
+        }
+        http_request
+    }
+    
+    /// Parse a chat completions response body
+    pub(super) fn parse_completion(body: &str) -> Result<Completion> {
+        let response: LLMResponse = serde_json::from_str(body)?;
+        let choice = response.choices
+            .into_iter()
+            .next()
+            .ok_or(Error::EmptyResponse)?;
+        
+        Ok(Completion {
+            id: response.id,
+            model: response.model,
+            message: choice.message,
+            finish_reason: choice.finish_reason.as_deref().map(FinishReason::from_provider),
+            usage: response.usage.map(Into::into),
+            cost: None,
+        })
+    }
+    
+    /// Build the embeddings body, shared with Azure deployments
+    pub(super) fn embeddings_body<'a>(request: &EmbeddingRequest<'a>) -> EmbeddingsBody<'a> {
+        EmbeddingsBody {
+            model: request.model,
+            input: request.inputs,
+            dimensions: request.dimensions,
+            encoding_format: "float",
+        }
+    }
+    
+    /// Parse an embeddings response body, restoring input order
+    pub(super) fn parse_embeddings(body: &str) -> Result<Embeddings> {
+        let mut response: EmbeddingsResponse = serde_json::from_str(body)?;
+        response.data.sort_by_key(|data| data.index);
+        
+        Ok(Embeddings {
+            vectors: response.data.into_iter().map(|data| data.embedding).collect(),
+            usage: response.usage.map(Into::into),
+        })
+    }
+    
+    /// Extract the text delta from a streamed chunk
+    pub(super) fn parse_event(event: &SseEvent) -> Result<Vec<StreamEvent>> {
+        if event.data == "[DONE]" {
+            return Ok(vec![StreamEvent::Done]);
+        }
+        // Proxies may send `event:`-only or empty frames
+        if event.data.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let chunk: ChunkResponse = serde_json::from_str(&event.data)
+            .map_err(|e| Error::Stream(format!("Failed to parse stream chunk: {}", e)))?;
+        
+        let text = chunk.choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.delta.content)
+            .filter(|text| !text.is_empty());
+        
+        Ok(text.map(StreamEvent::Delta)
+            .into_iter()
+            .chain(chunk.usage.map(|usage| StreamEvent::Usage(usage.into())))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl LLMProvider for OpenAIProvider {
+    fn name(&self) -> &str {
+        "OpenAI"
+    }
+    
+    fn default_base_url(&self) -> &str {
+        DEFAULT_BASE_URL
+    }
+    
+    fn default_embedding_model(&self) -> Option<&str> {
+        Some(DEFAULT_EMBEDDING_MODEL)
+    }
+    
+    fn max_embedding_batch(&self) -> usize {
+        MAX_EMBEDDING_BATCH
+    }
+    
+    async fn embed(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &EmbeddingRequest<'_>,
+    ) -> Result<Embeddings> {
+        let http_request = Self::authorize(post(client, config, DEFAULT_BASE_URL, "embeddings"), config)
+            .json(&Self::embeddings_body(request));
+        let body = send_request(config, http_request).await?;
+        Self::parse_embeddings(&body)
+    }
+    
+    async fn send(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+    ) -> Result<Completion> {
+        let body = send_request(config, self.build_request(client, config, request, false)).await?;
+        Self::parse_completion(&body)
+    }
+    
+    async fn stream(
+        &self,
+        client: &reqwest::Client,
+        config: &LLMConfig,
+        request: &ChatRequest<'_>,
+    ) -> Result<TokenStream> {
+        let response = send_checked(config, self.build_request(client, config, request, true)).await?;
+        Ok(sse::token_stream(response, Self::parse_event))
+    }
+}
@@ -0,0 +1,178 @@
+use crate::components::llm_interface::{TokenStream, Usage};
+use crate::{Error, Result};
+use futures::stream::{self, BoxStream, StreamExt};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// A single Server-Sent Event
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct SseEvent {
+    pub(crate) event: Option<String>,
+    pub(crate) data: String,
+}
+
+/// Something a provider found in one event
+#[derive(Debug)]
+pub(crate) enum StreamEvent {
+    /// A piece of assistant text
+    Delta(String),
+    /// Token counts for the reply so far
+    Usage(Usage),
+    /// The provider signalled the end of the stream
+    Done,
+}
+
+/// Shared slot the stream records reported usage in
+pub(crate) type UsageSlot = Arc<Mutex<Option<Usage>>>;
+
+/// Apply one parsed event, queueing text and recording usage
+/// 
+/// Returns `true` once the provider has signalled the end of the stream.
+pub(crate) fn apply(events: Vec<StreamEvent>, pending: &mut VecDeque<String>, usage: &UsageSlot) -> bool {
+    for event in events {
+        match event {
+            StreamEvent::Delta(text) => pending.push_back(text),
+            StreamEvent::Usage(reported) => {
+                usage.lock().unwrap().get_or_insert_with(Usage::default).merge(reported);
+            }
+            StreamEvent::Done => return true,
+        }
+    }
+    false
+}
+
+/// Incremental parser for the `text/event-stream` format
+/// 
+/// Bytes are buffered until a full line is available so multi-byte
+/// characters split across network chunks are decoded correctly.
+#[derive(Debug, Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+    current: SseEvent,
+    has_data: bool,
+}
+
+impl SseParser {
+    /// Feed a chunk of bytes and return any events it completed
+    fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+            
+            if let Some(event) = self.process_line(line) {
+                events.push(event);
+            }
+        }
+        
+        events
+    }
+    
+    /// Flush a trailing event that was not followed by a blank line
+    fn finish(&mut self) -> Option<SseEvent> {
+        let line = String::from_utf8_lossy(&std::mem::take(&mut self.buffer)).into_owned();
+        self.process_line(line.trim_end_matches('\r'));
+        self.process_line("")
+    }
+    
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            if !self.has_data && self.current.event.is_none() {
+                return None;
+            }
+            self.has_data = false;
+            return Some(std::mem::take(&mut self.current));
+        }
+        
+        // Lines starting with a colon are comments (often used as keep-alives)
+        if line.starts_with(':') {
+            return None;
+        }
+        
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        
+        match field {
+            "event" => self.current.event = Some(value.to_string()),
+            "data" => {
+                if self.has_data {
+                    self.current.data.push('\n');
+                }
+                self.current.data.push_str(value);
+                self.has_data = true;
+            }
+            _ => {}
+        }
+        
+        None
+    }
+}
+
+/// Parse an SSE response body into raw events
+fn events(response: reqwest::Response) -> BoxStream<'static, Result<SseEvent>> {
+    let state = (response.bytes_stream(), SseParser::default(), VecDeque::new(), false);
+    
+    stream::unfold(state, |(mut bytes, mut parser, mut pending, mut finished)| async move {
+        loop {
+            if let Some(event) = pending.pop_front() {
+                return Some((Ok(event), (bytes, parser, pending, finished)));
+            }
+            if finished {
+                return None;
+            }
+            
+            match bytes.next().await {
+                Some(Ok(chunk)) => pending.extend(parser.push(&chunk)),
+                Some(Err(e)) => {
+                    return Some((Err(Error::Request(e)), (bytes, parser, pending, true)));
+                }
+                None => {
+                    finished = true;
+                    pending.extend(parser.finish());
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Turn an SSE response into a stream of text deltas
+/// 
+/// `parse` maps each event to what it carries, nothing for pings and
+/// metadata; the stream ends at the first `Done` event, error, or when
+/// the connection closes. Reported usage is available from the stream.
+pub(crate) fn token_stream(
+    response: reqwest::Response,
+    parse: fn(&SseEvent) -> Result<Vec<StreamEvent>>,
+) -> TokenStream {
+    let usage = UsageSlot::default();
+    let state = (events(response), VecDeque::<String>::new(), false, usage.clone());
+    
+    let deltas = stream::unfold(state, move |(mut events, mut pending, mut done, usage)| async move {
+        loop {
+            if let Some(text) = pending.pop_front() {
+                return Some((Ok(text), (events, pending, done, usage)));
+            }
+            if done {
+                return None;
+            }
+            
+            let event = match events.next().await? {
+                Ok(event) => event,
+                Err(e) => return Some((Err(e), (events, pending, true, usage))),
+            };
+            
+            match parse(&event) {
+                Ok(parsed) => done = apply(parsed, &mut pending, &usage),
+                Err(e) => return Some((Err(e), (events, VecDeque::new(), true, usage))),
+            }
+        }
+    });
+    
+    TokenStream::with_usage(deltas, usage)
+}
@@ -0,0 +1,217 @@
+use crate::components::{
+    AIComponent, Chunk, Document, LLMInterface, MetadataFilter, NamedComponent, SearchResult,
+    VectorStore,
+};
+use crate::components::prompt_manager::render;
+use crate::{Error, Result};
+use std::collections::HashMap;
+use regex::Regex;
+use tracing::{info, warn};
+
+/// Prompt asking the model to answer from numbered sources and cite them
+pub const DEFAULT_RAG_TEMPLATE: &str = "Answer the question using only the numbered sources below. \
+Cite every source you use by its number in square brackets, such as [1] or [2][3]. \
+If the sources do not contain the answer, say that you do not know.
+
+Sources:
+{sources}
+
+Question: {question}";
+
+/// Reply given when retrieval finds nothing, without calling the model
+const NO_SOURCES_ANSWER: &str = "No indexed documents are relevant to this question.";
+
+/// Chunks retrieved when no other count is set
+const DEFAULT_TOP_K: usize = 4;
+
+/// Answer from the model with the chunks behind it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RagAnswer {
+    pub answer: String,
+    /// Retrieved chunks the answer cites, in order of first citation
+    pub cited: Vec<SearchResult>,
+    /// Every chunk put in the prompt, numbered from 1 in this order
+    pub retrieved: Vec<SearchResult>,
+}
+
+/// Describe where a chunk came from, such as `guide.md > Install`
+pub fn source_label(document: &Document) -> String {
+    let source = document.metadata.get("source")
+        .and_then(|source| source.as_str())
+        .unwrap_or(&document.id);
+    match document.metadata.get("heading").and_then(|heading| heading.as_str()) {
+        Some(heading) => format!("{} > {}", source, heading),
+        None => source.to_string(),
+    }
+}
+
+/// Retrieval-augmented generation over a vector store
+/// 
+/// A question is embedded, the `top_k` closest chunks are numbered and
+/// rendered into the prompt template, and the model is asked to cite
+/// them as `[n]`. The citations in its reply select the
+/// chunks returned with the answer.
+#[derive(Debug)]
+pub struct RagPipeline {
+    name: String,
+    description: String,
+    store: VectorStore,
+    template: String,
+    top_k: usize,
+    filter: Option<MetadataFilter>,
+}
+
+impl RagPipeline {
+    /// Create a pipeline answering from a store with the default template
+    pub fn new(store: VectorStore) -> Self {
+        Self {
+            name: "RAG Pipeline".to_string(),
+            description: "Answers questions from indexed documents with citations".to_string(),
+            store,
+            template: DEFAULT_RAG_TEMPLATE.to_string(),
+            top_k: DEFAULT_TOP_K,
+            filter: None,
+        }
+    }
+    
+    /// Use another prompt template with `{sources}` and `{question}` placeholders
+    pub fn with_template(mut self, template: impl Into<String>) -> Result<Self> {
+        let template = template.into();
+        for placeholder in ["{sources}", "{question}"] {
+            if !template.contains(placeholder) {
+                return Err(Error::InvalidTemplate(format!("RAG template must contain {}", placeholder)));
+            }
+        }
+        
+        // Any other placeholder would reach the model unfilled
+        render(&template, &prompt_variables(String::new(), String::new()))?;
+        
+        self.template = template;
+        Ok(self)
+    }
+    
+    /// Set how many chunks are retrieved per question
+    pub fn with_top_k(mut self, top_k: usize) -> Self {
+        self.top_k = top_k.max(1);
+        self
+    }
+    
+    /// Only retrieve chunks whose metadata matches `filter`
+    pub fn with_filter(mut self, filter: MetadataFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+    
+    /// Get the vector store
+    pub fn store(&self) -> &VectorStore {
+        &self.store
+    }
+    
+    /// Get the vector store for changes
+    pub fn store_mut(&mut self) -> &mut VectorStore {
+        &mut self.store
+    }
+    
+    /// Embed chunks and add them to the store with their metadata
+    pub async fn index(&mut self, llm: &LLMInterface, chunks: &[Chunk]) -> Result<usize> {
+        let texts = chunks.iter().map(|chunk| (chunk.text.clone(), chunk.metadata())).collect();
+        let ids = self.store.add_texts(llm, texts).await?;
+        Ok(ids.len())
+    }
+    
+    /// Find the chunks closest to a question
+    pub async fn retrieve(&self, llm: &LLMInterface, question: &str) -> Result<Vec<SearchResult>> {
+        self.store.search_text(llm, question, self.top_k, self.filter.as_ref()).await
+    }
+    
+    /// Render the prompt for a question and its numbered sources
+    pub fn render_prompt(&self, question: &str, sources: &[SearchResult]) -> Result<String> {
+        let sources = sources.iter()
+            .enumerate()
+            .map(|(i, result)| format!("[{}] ({})\n{}", i + 1, source_label(&result.document), result.document.text.trim()))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        
+        render(&self.template, &prompt_variables(sources, question.to_string()))
+    }
+    
+    /// Answer a question from the store, returning the chunks the answer cites
+    pub async fn ask(&self, llm: &LLMInterface, question: &str) -> Result<RagAnswer> {
+        let retrieved = self.retrieve(llm, question).await?;
+        if retrieved.is_empty() {
+            warn!("No chunks retrieved for question: {}", question);
+            return Ok(RagAnswer {
+                answer: NO_SOURCES_ANSWER.to_string(),
+                cited: Vec::new(),
+                retrieved,
+            });
+        }
+        
+        info!("Answering from {} retrieved chunks", retrieved.len());
+        let completion = llm.generate(&self.render_prompt(question, &retrieved)?).await?;
+        let answer = completion.text().to_string();
+        
+        let cited = cited_numbers(&answer, retrieved.len())
+            .into_iter()
+            .map(|number| retrieved[number - 1].clone())
+            .collect();
+        Ok(RagAnswer {
+            answer,
+            cited,
+            retrieved,
+        })
+    }
+}
+
+/// Source numbers cited as `[n]` or `[n, m]`, deduplicated in order of first use
+/// Variables for the `{sources}` and `{question}` placeholders
+fn prompt_variables(sources: String, question: String) -> HashMap<String, String> {
+    HashMap::from([("sources".to_string(), sources), ("question".to_string(), question)])
+}
+
+fn cited_numbers(answer: &str, sources: usize) -> Vec<usize> {
+    let citation = Regex::new(r"\[(\d+(?:\s*,\s*\d+)*)\]").unwrap();
+    let mut numbers = Vec::new();
+    
+    for caps in citation.captures_iter(answer) {
+        for number in caps[1].split(',').filter_map(|number| number.trim().parse::<usize>().ok()) {
+            if (1..=sources).contains(&number) && !numbers.contains(&number) {
+                numbers.push(number);
+            }
+        }
+    }
+    
+    numbers
+}
+
+impl NamedComponent for RagPipeline {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+    
+    fn get_description(&self) -> &str {
+        &self.description
+    }
+}
+
+impl AIComponent for RagPipeline {
+    fn initialize(&mut self) -> Result<()> {
+        info!("Initializing RAG pipeline (top {} chunks)", self.top_k);
+        self.store.initialize()
+    }
+    
+    fn process(&self) -> Result<()> {
+        if self.store.is_empty() {
+            warn!("No documents indexed yet");
+        }
+        self.store.process()
+    }
+    
+    fn name(&self) -> &str {
+        self.get_name()
+    }
+    
+    fn description(&self) -> &str {
+        self.get_description()
+    }
+}
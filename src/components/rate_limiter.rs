@@ -0,0 +1,180 @@
+use crate::components::llm_interface::Message;
+use crate::components::tokenizer::Tokenizer;
+use reqwest::header::HeaderMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Token bucket that refills continuously up to its capacity
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    available: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    /// Create a full bucket holding one minute's worth of quota
+    fn per_minute(limit: u32) -> Self {
+        Self {
+            capacity: limit as f64,
+            available: limit as f64,
+            refill_per_sec: limit as f64 / 60.0,
+            last_refill: Instant::now(),
+        }
+    }
+    
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.available = (self.available + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+    
+    /// Time until `amount` is available
+    fn wait_for(&self, amount: f64) -> Duration {
+        let missing = amount - self.available;
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing / self.refill_per_sec)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Buckets {
+    requests: Option<Bucket>,
+    tokens: Option<Bucket>,
+}
+
+/// Client-side rate limiter for requests and tokens per minute
+/// 
+/// `LLMInterface` waits on the limiter before each call. Wrap it in an
+/// `Arc` and hand it to several interfaces to share one quota between
+/// them; it also corrects itself from the remaining-quota headers the
+/// provider returns, which accounts for other jobs using the same key.
+#[derive(Debug)]
+pub struct RateLimiter {
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    /// Create a limiter; `None` leaves that dimension unlimited
+    pub fn new(requests_per_minute: Option<u32>, tokens_per_minute: Option<u32>) -> Self {
+        Self {
+            buckets: Mutex::new(Buckets {
+                requests: requests_per_minute.filter(|&l| l > 0).map(Bucket::per_minute),
+                tokens: tokens_per_minute.filter(|&l| l > 0).map(Bucket::per_minute),
+            }),
+        }
+    }
+    
+    /// Wait until one request using `tokens` tokens fits in the quota, then reserve it
+    /// 
+    /// Requests larger than the whole token budget wait for a full bucket
+    /// rather than forever.
+    pub async fn acquire(&self, tokens: u32) {
+        loop {
+            let wait = {
+                let mut buckets = self.buckets.lock().unwrap();
+                let now = Instant::now();
+                let Buckets { requests, tokens: token_bucket } = &mut *buckets;
+                
+                let needed_tokens = token_bucket
+                    .as_ref()
+                    .map_or(0.0, |b| (tokens as f64).min(b.capacity));
+                let wait = [
+                    requests.as_mut().map(|b| {
+                        b.refill(now);
+                        b.wait_for(1.0)
+                    }),
+                    token_bucket.as_mut().map(|b| {
+                        b.refill(now);
+                        b.wait_for(needed_tokens)
+                    }),
+                ]
+                .into_iter()
+                .flatten()
+                .max()
+                .unwrap_or(Duration::ZERO);
+                
+                if wait.is_zero() {
+                    if let Some(bucket) = requests {
+                        bucket.available -= 1.0;
+                    }
+                    if let Some(bucket) = token_bucket {
+                        bucket.available -= needed_tokens;
+                    }
+                    return;
+                }
+                wait
+            };
+            
+            debug!("Rate limit reached; waiting {:?}", wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+    
+    /// Lower the available quota to what the provider reports as remaining
+    /// 
+    /// Understands OpenAI `x-ratelimit-remaining-*` and Anthropic
+    /// `anthropic-ratelimit-*-remaining` headers.
+    pub fn update_from_headers(&self, headers: &HeaderMap) {
+        let header = |names: &[&str]| {
+            names.iter()
+                .filter_map(|name| headers.get(*name))
+                .filter_map(|value| value.to_str().ok())
+                .find_map(|value| value.trim().parse::<f64>().ok())
+        };
+        
+        let remaining_requests = header(&[
+            "x-ratelimit-remaining-requests",
+            "anthropic-ratelimit-requests-remaining",
+        ]);
+        let remaining_tokens = header(&[
+            "x-ratelimit-remaining-tokens",
+            "anthropic-ratelimit-tokens-remaining",
+        ]);
+        
+        let mut guard = self.buckets.lock().unwrap();
+        let buckets = &mut *guard;
+        let now = Instant::now();
+        for (bucket, remaining) in [
+            (buckets.requests.as_mut(), remaining_requests),
+            (buckets.tokens.as_mut(), remaining_tokens),
+        ] {
+            if let (Some(bucket), Some(remaining)) = (bucket, remaining) {
+                bucket.refill(now);
+                bucket.available = bucket.available.min(remaining);
+            }
+        }
+    }
+    
+    /// Get the number of requests that could be sent right now
+    pub fn available_requests(&self) -> Option<u32> {
+        let mut buckets = self.buckets.lock().unwrap();
+        buckets.requests.as_mut().map(|b| {
+            b.refill(Instant::now());
+            b.available.max(0.0) as u32
+        })
+    }
+    
+    /// Get the number of tokens that could be used right now
+    pub fn available_tokens(&self) -> Option<u32> {
+        let mut buckets = self.buckets.lock().unwrap();
+        buckets.tokens.as_mut().map(|b| {
+            b.refill(Instant::now());
+            b.available.max(0.0) as u32
+        })
+    }
+}
+
+/// Estimate the tokens a request will use: the prompt plus the output limit
+pub fn estimate_request_tokens(
+    tokenizer: &Tokenizer,
+    messages: &[Message],
+    max_tokens: Option<u32>,
+) -> u32 {
+    tokenizer.count_conversation_tokens(messages) as u32 + max_tokens.unwrap_or(0)
+}
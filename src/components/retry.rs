@@ -0,0 +1,120 @@
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Retry policy for transient API failures
+/// 
+/// Rate limits (429), server errors (500, 502, 503, 504), Anthropic's
+/// overloaded status (529), timeouts and connection failures are retried
+/// with exponential backoff. Client errors such as 400 or 401 are never
+/// retried since repeating the request cannot fix them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total attempts including the first; 1 disables retries
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on each following one
+    pub base_delay_ms: u64,
+    /// Upper bound on the computed backoff delay
+    pub max_delay_ms: u64,
+    /// Random spread applied to each delay as a fraction (0.2 = ±20%)
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 30_000,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that never retries
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+    
+    /// Check if a response status is worth retrying
+    pub fn is_retryable_status(status: StatusCode) -> bool {
+        matches!(status.as_u16(), 408 | 429 | 500 | 502 | 503 | 504 | 529)
+    }
+    
+    /// Check if a transport error is worth retrying
+    pub fn is_retryable_error(error: &reqwest::Error) -> bool {
+        error.is_timeout() || error.is_connect() || error.is_request()
+    }
+    
+    /// Delay before retry number `attempt` (1 for the first retry)
+    /// 
+    /// Server hints in `Retry-After` or the rate-limit reset headers take
+    /// precedence over the computed backoff. Both are capped at `max_delay_ms`.
+    pub fn delay(&self, attempt: u32, headers: Option<&HeaderMap>) -> Duration {
+        if let Some(hint) = headers.and_then(server_delay) {
+            return hint.min(Duration::from_millis(self.max_delay_ms));
+        }
+        
+        let exponent = attempt.saturating_sub(1).min(31);
+        let backoff = self.base_delay_ms.saturating_mul(1u64 << exponent);
+        let spread = 1.0 + self.jitter * (2.0 * fastrand::f64() - 1.0);
+        let delay = (backoff as f64 * spread).max(0.0) as u64;
+        
+        Duration::from_millis(delay.min(self.max_delay_ms))
+    }
+}
+
+/// Read how long the server asked us to wait
+pub(crate) fn server_delay(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    
+    if let Some(ms) = header("retry-after-ms").and_then(|v| v.trim().parse::<f64>().ok()) {
+        return Some(Duration::from_secs_f64(ms.max(0.0) / 1000.0));
+    }
+    if let Some(secs) = header("retry-after").and_then(|v| v.trim().parse::<f64>().ok()) {
+        return Some(Duration::from_secs_f64(secs.max(0.0)));
+    }
+    
+    // OpenAI reports when each exhausted limit resets, e.g. "1s" or "6m0s"
+    ["requests", "tokens"]
+        .iter()
+        .filter(|limit| header(&format!("x-ratelimit-remaining-{}", limit)) == Some("0"))
+        .filter_map(|limit| header(&format!("x-ratelimit-reset-{}", limit)).and_then(parse_duration))
+        .max()
+}
+
+/// Parse a duration such as "20ms", "1.5s" or "6m0s"
+pub(crate) fn parse_duration(value: &str) -> Option<Duration> {
+    let mut total = 0.0;
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_end].parse().ok()?;
+        rest = &rest[number_end..];
+        
+        let unit_end = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+        let seconds = match &rest[..unit_end] {
+            "ms" => number / 1000.0,
+            "s" | "" => number,
+            "m" => number * 60.0,
+            "h" => number * 3600.0,
+            _ => return None,
+        };
+        total += seconds;
+        rest = &rest[unit_end..];
+    }
+    
+    Some(Duration::from_secs_f64(total))
+}
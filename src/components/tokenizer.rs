@@ -0,0 +1,115 @@
+use crate::components::llm_interface::Message;
+use serde::{Deserialize, Serialize};
+use tiktoken_rs::{cl100k_base_singleton, o200k_base_singleton, CoreBPE};
+
+/// Tokens added around every chat message by the chat format
+const TOKENS_PER_MESSAGE: usize = 3;
+
+/// Tokens that prime the assistant reply
+const REPLY_PRIMING_TOKENS: usize = 3;
+
+/// Ratio of Anthropic tokens to `cl100k_base` tokens
+/// 
+/// Anthropic does not publish its tokenizer. Claude models split English
+/// text into somewhat more tokens than `cl100k_base`, so counts are scaled
+/// up; erring high keeps context-window checks on the safe side.
+const ANTHROPIC_TOKEN_RATIO: f64 = 1.15;
+
+/// Byte pair encoding used to split text into tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    /// GPT-3.5, GPT-4 and GPT-4 Turbo
+    Cl100kBase,
+    /// GPT-4o, GPT-4.1 and the o-series reasoning models
+    O200kBase,
+}
+
+impl Encoding {
+    fn bpe(self) -> &'static CoreBPE {
+        match self {
+            Encoding::Cl100kBase => cl100k_base_singleton(),
+            Encoding::O200kBase => o200k_base_singleton(),
+        }
+    }
+}
+
+/// Offline token counter for a model
+/// 
+/// Counts are exact for OpenAI models and an approximation for Anthropic
+/// models. The BPE tables are bundled with the crate and loaded once.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tokenizer {
+    encoding: Encoding,
+    /// Multiplier applied to counts for models using a different tokenizer
+    ratio: Option<f64>,
+}
+
+impl Tokenizer {
+    /// Create an exact tokenizer for an encoding
+    pub fn new(encoding: Encoding) -> Self {
+        Self {
+            encoding,
+            ratio: None,
+        }
+    }
+    
+    /// Pick the tokenizer for a model name
+    /// 
+    /// Unknown models fall back to `cl100k_base`, which is close for most
+    /// open models served through OpenAI-compatible APIs.
+    pub fn for_model(model: &str) -> Self {
+        let model = model.rsplit('/').next().unwrap_or(model);
+        
+        if model.starts_with("claude") {
+            return Self {
+                encoding: Encoding::Cl100kBase,
+                ratio: Some(ANTHROPIC_TOKEN_RATIO),
+            };
+        }
+        
+        let o200k_prefixes = ["gpt-4o", "gpt-4.1", "gpt-4.5", "gpt-5", "chatgpt-4o", "o1", "o3", "o4"];
+        if o200k_prefixes.iter().any(|prefix| model.starts_with(prefix)) {
+            Self::new(Encoding::O200kBase)
+        } else {
+            Self::new(Encoding::Cl100kBase)
+        }
+    }
+    
+    /// Get the underlying encoding
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+    
+    /// Check if counts are exact rather than approximated
+    pub fn is_exact(&self) -> bool {
+        self.ratio.is_none()
+    }
+    
+    /// Count the tokens in a piece of text
+    pub fn count_tokens(&self, text: &str) -> usize {
+        let count = self.encoding.bpe().encode_ordinary(text).len();
+        match self.ratio {
+            Some(ratio) => (count as f64 * ratio).ceil() as usize,
+            None => count,
+        }
+    }
+    
+    /// Count the tokens a single message takes in a chat request
+    pub fn count_message_tokens(&self, message: &Message) -> usize {
+        let tool_calls: usize = message.tool_calls.iter()
+            .map(|call| self.count_tokens(&call.name) + self.count_tokens(&call.arguments.to_string()))
+            .sum();
+        
+        TOKENS_PER_MESSAGE + self.count_tokens(&message.content) + tool_calls
+    }
+    
+    /// Count the prompt tokens of a chat request made of these messages
+    /// 
+    /// Includes the per-message overhead of the chat format, following
+    /// OpenAI's published counting recipe.
+    pub fn count_conversation_tokens(&self, messages: &[Message]) -> usize {
+        let messages: usize = messages.iter().map(|m| self.count_message_tokens(m)).sum();
+        messages + REPLY_PRIMING_TOKENS
+    }
+}
@@ -0,0 +1,84 @@
+use crate::components::llm_interface::{Tool, ToolCall};
+use crate::{Error, Result};
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+/// Result returned by tool handlers; any error type can be propagated with `?`
+pub type ToolResult = std::result::Result<String, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Async function that executes a tool call and returns its result text
+pub type ToolHandler = Arc<dyn Fn(serde_json::Value) -> BoxFuture<'static, Result<String>> + Send + Sync>;
+
+/// Set of tools the model may call, with the Rust handlers that run them
+/// 
+/// Pass a registry to `LLMInterface::chat_with_tools` to let the model
+/// call tools until it produces a final answer.
+#[derive(Default, Clone)]
+pub struct ToolRegistry {
+    tools: Vec<Tool>,
+    handlers: HashMap<String, ToolHandler>,
+}
+
+impl ToolRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Register a tool and the handler that runs it
+    /// 
+    /// The handler receives the arguments parsed from the model's call.
+    /// Registering a tool with an existing name replaces it.
+    pub fn register<F, Fut>(&mut self, tool: Tool, handler: F)
+    where
+        F: Fn(serde_json::Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ToolResult> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let name = tool.name.clone();
+        let tool_handler: ToolHandler = Arc::new(move |arguments| {
+            let handler = handler.clone();
+            let name = name.clone();
+            Box::pin(async move {
+                handler(arguments).await.map_err(|e| Error::Tool {
+                    name,
+                    message: e.to_string(),
+                })
+            })
+        });
+        
+        self.tools.retain(|t| t.name != tool.name);
+        self.handlers.insert(tool.name.clone(), tool_handler);
+        self.tools.push(tool);
+    }
+    
+    /// Get the definitions of all registered tools
+    pub fn definitions(&self) -> &[Tool] {
+        &self.tools
+    }
+    
+    /// Check if no tools are registered
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+    
+    /// Run the handler for a tool call
+    pub async fn call(&self, call: &ToolCall) -> Result<String> {
+        let handler = self.handlers
+            .get(&call.name)
+            .ok_or_else(|| Error::UnknownTool(call.name.clone()))?;
+        
+        handler(call.arguments.clone()).await
+    }
+}
+
+impl fmt::Debug for ToolRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolRegistry")
+            .field("tools", &self.tools)
+            .finish()
+    }
+}
@@ -0,0 +1,141 @@
+use crate::components::conversation::Conversation;
+use crate::components::llm_interface::{Message, Role};
+use serde::{Deserialize, Serialize};
+
+/// Start of the system message holding the summary of dropped turns
+const SUMMARY_PREFIX: &str = "Summary of the earlier conversation:\n";
+
+/// Instructions for the call that summarizes older turns
+pub(crate) const SUMMARY_INSTRUCTIONS: &str = "Summarize the conversation below so it can be \
+    continued without the full transcript. Keep facts, decisions, names, numbers and open \
+    questions. Reply with the summary only.";
+
+/// How a conversation is shortened before it is sent
+/// 
+/// System prompts are always kept, and a turn (a user message with the
+/// replies and tool results that follow it) is kept or dropped as a
+/// whole. The strategy edits the conversation in place, so dropped turns
+/// are gone from it afterwards.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum TruncationStrategy {
+    /// Send the history unchanged
+    #[default]
+    Disabled,
+    /// Drop the oldest turns once the history no longer fits the context window
+    DropOldest,
+    /// Always keep only the most recent `turns` turns
+    SlidingWindow { turns: usize },
+    /// Once the history no longer fits, replace all but the most recent
+    /// `keep_turns` turns with a summary written by the model
+    Summarize { keep_turns: usize },
+}
+
+/// System messages and the rest of the history grouped into turns
+struct Turns {
+    system: Vec<Message>,
+    turns: Vec<Vec<Message>>,
+}
+
+impl Turns {
+    fn split(messages: &[Message]) -> Self {
+        let mut system = Vec::new();
+        let mut turns: Vec<Vec<Message>> = Vec::new();
+        
+        for message in messages.iter().cloned() {
+            match (message.role, turns.last_mut()) {
+                (Role::System, _) => system.push(message),
+                (Role::User, _) | (_, None) => turns.push(vec![message]),
+                (_, Some(turn)) => turn.push(message),
+            }
+        }
+        
+        Self { system, turns }
+    }
+    
+    fn to_messages(&self) -> Vec<Message> {
+        self.system.iter().chain(self.turns.iter().flatten()).cloned().collect()
+    }
+}
+
+/// Keep only the most recent `turns` turns, returning how many were dropped
+/// 
+/// The latest turn holds the pending question, so at least one is kept.
+pub(crate) fn keep_recent_turns(conversation: &mut Conversation, turns: usize) -> usize {
+    let mut split = Turns::split(conversation.messages());
+    let dropped = split.turns.len().saturating_sub(turns.max(1));
+    
+    if dropped > 0 {
+        split.turns.drain(..dropped);
+        conversation.replace_messages(split.to_messages());
+    }
+    dropped
+}
+
+/// Drop the oldest turns until `fits` accepts the history
+/// 
+/// The latest turn is always kept, even if it does not fit on its own.
+/// Returns how many turns were dropped.
+pub(crate) fn drop_oldest_turns(
+    conversation: &mut Conversation,
+    fits: impl Fn(&[Message]) -> bool,
+) -> usize {
+    let mut split = Turns::split(conversation.messages());
+    let mut dropped = 0;
+    
+    while split.turns.len() > 1 && !fits(&split.to_messages()) {
+        split.turns.remove(0);
+        dropped += 1;
+    }
+    
+    if dropped > 0 {
+        conversation.replace_messages(split.to_messages());
+    }
+    dropped
+}
+
+/// Transcript of everything before the most recent `keep_turns` turns
+/// 
+/// Includes the summary left by an earlier call so it is folded into the
+/// new one. Returns `None` when there is nothing to summarize.
+pub(crate) fn older_turns_transcript(conversation: &Conversation, keep_turns: usize) -> Option<String> {
+    let split = Turns::split(conversation.messages());
+    let older = split.turns.len().saturating_sub(keep_turns.max(1));
+    if older == 0 {
+        return None;
+    }
+    
+    let mut lines: Vec<String> = split.system.iter()
+        .filter_map(|m| m.content.strip_prefix(SUMMARY_PREFIX))
+        .map(|summary| format!("Earlier summary: {}", summary))
+        .collect();
+    
+    for message in split.turns[..older].iter().flatten() {
+        match message.role {
+            Role::System => {}
+            Role::User => lines.push(format!("User: {}", message.content)),
+            Role::Assistant => {
+                if !message.content.is_empty() {
+                    lines.push(format!("Assistant: {}", message.content));
+                }
+                for call in &message.tool_calls {
+                    lines.push(format!("Assistant called {} with {}", call.name, call.arguments));
+                }
+            }
+            Role::Tool => lines.push(format!("Tool result: {}", message.content)),
+        }
+    }
+    
+    Some(lines.join("\n"))
+}
+
+/// Replace everything before the most recent `keep_turns` turns with a summary
+pub(crate) fn replace_older_turns(conversation: &mut Conversation, keep_turns: usize, summary: &str) {
+    let mut split = Turns::split(conversation.messages());
+    let older = split.turns.len().saturating_sub(keep_turns.max(1));
+    
+    split.turns.drain(..older);
+    split.system.retain(|m| !m.content.starts_with(SUMMARY_PREFIX));
+    split.system.push(Message::system(format!("{}{}", SUMMARY_PREFIX, summary)));
+    conversation.replace_messages(split.to_messages());
+}
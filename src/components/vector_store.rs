@@ -0,0 +1,513 @@
+use crate::components::{AIComponent, LLMInterface, NamedComponent};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Version written to store files
+const FILE_VERSION: u32 = 1;
+
+/// Metadata attached to a document, such as its source file
+pub type Metadata = serde_json::Map<String, Value>;
+
+/// How query vectors are compared with stored ones
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Similarity {
+    /// Cosine of the angle between the vectors, from -1 to 1
+    #[default]
+    Cosine,
+    /// Plain dot product; the same ranking as cosine for normalized vectors
+    DotProduct,
+}
+
+impl Similarity {
+    /// Score two vectors of the same length
+    pub fn score(&self, a: &[f32], b: &[f32]) -> f32 {
+        let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        match self {
+            Similarity::DotProduct => dot,
+            Similarity::Cosine => {
+                let norms = norm(a) * norm(b);
+                if norms > 0.0 { dot / norms } else { 0.0 }
+            }
+        }
+    }
+}
+
+fn norm(vector: &[f32]) -> f32 {
+    vector.iter().map(|x| x * x).sum::<f32>().sqrt()
+}
+
+/// Text stored with its embedding and metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub metadata: Metadata,
+    pub embedding: Vec<f32>,
+}
+
+impl Document {
+    /// Create a document without metadata
+    pub fn new(id: impl Into<String>, text: impl Into<String>, embedding: Vec<f32>) -> Self {
+        Self {
+            id: id.into(),
+            text: text.into(),
+            metadata: Metadata::new(),
+            embedding,
+        }
+    }
+    
+    /// Add a metadata entry
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// Condition on document metadata applied before ranking
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataFilter {
+    /// The key is present with exactly this value
+    Equals(String, Value),
+    /// The key is present with one of these values
+    OneOf(String, Vec<Value>),
+    /// The key is present with any value
+    Exists(String),
+    /// Every filter matches
+    All(Vec<MetadataFilter>),
+    /// At least one filter matches
+    Any(Vec<MetadataFilter>),
+    /// The filter does not match
+    Not(Box<MetadataFilter>),
+}
+
+impl MetadataFilter {
+    /// Match documents whose `key` equals `value`
+    pub fn equals(key: impl Into<String>, value: impl Into<Value>) -> Self {
+        MetadataFilter::Equals(key.into(), value.into())
+    }
+    
+    /// Match documents whose `key` is one of `values`
+    pub fn one_of<V: Into<Value>>(key: impl Into<String>, values: impl IntoIterator<Item = V>) -> Self {
+        MetadataFilter::OneOf(key.into(), values.into_iter().map(Into::into).collect())
+    }
+    
+    /// Match documents that have `key`
+    pub fn exists(key: impl Into<String>) -> Self {
+        MetadataFilter::Exists(key.into())
+    }
+    
+    /// Match documents matching both this filter and `other`
+    pub fn and(self, other: MetadataFilter) -> Self {
+        match self {
+            MetadataFilter::All(mut filters) => {
+                filters.push(other);
+                MetadataFilter::All(filters)
+            }
+            filter => MetadataFilter::All(vec![filter, other]),
+        }
+    }
+    
+    /// Check a document's metadata against the filter
+    pub fn matches(&self, metadata: &Metadata) -> bool {
+        match self {
+            MetadataFilter::Equals(key, value) => metadata.get(key) == Some(value),
+            MetadataFilter::OneOf(key, values) => metadata.get(key).is_some_and(|v| values.contains(v)),
+            MetadataFilter::Exists(key) => metadata.contains_key(key),
+            MetadataFilter::All(filters) => filters.iter().all(|filter| filter.matches(metadata)),
+            MetadataFilter::Any(filters) => filters.iter().any(|filter| filter.matches(metadata)),
+            MetadataFilter::Not(filter) => !filter.matches(metadata),
+        }
+    }
+}
+
+/// Document returned by a search with its similarity to the query
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    pub document: Document,
+    pub score: f32,
+}
+
+/// On-disk layout of a store
+#[derive(Debug, Serialize, Deserialize)]
+struct StoreFile {
+    version: u32,
+    similarity: Similarity,
+    embedding_model: Option<String>,
+    documents: Vec<Document>,
+}
+
+/// In-memory vector store for retrieval prototypes
+/// 
+/// Documents are kept in memory and searched exhaustively, which is fast
+/// enough for tens of thousands of chunks. The whole store is saved to
+/// and loaded from a single JSON file.
+/// 
+/// Texts added through an `LLMInterface` are embedded on the way in, and
+/// the store remembers the embedding model so it is not queried with
+/// vectors from another one.
+#[derive(Debug)]
+pub struct VectorStore {
+    name: String,
+    description: String,
+    similarity: Similarity,
+    embedding_model: Option<String>,
+    documents: Vec<Document>,
+    /// Position of each document in `documents` by ID
+    index: HashMap<String, usize>,
+    path: Option<PathBuf>,
+    next_id: usize,
+}
+
+impl VectorStore {
+    /// Create an empty store using cosine similarity
+    pub fn new() -> Self {
+        Self {
+            name: "Vector Store".to_string(),
+            description: "Stores embedded documents for similarity search".to_string(),
+            similarity: Similarity::default(),
+            embedding_model: None,
+            documents: Vec::new(),
+            index: HashMap::new(),
+            path: None,
+            next_id: 0,
+        }
+    }
+    
+    /// Set how vectors are compared
+    pub fn with_similarity(mut self, similarity: Similarity) -> Self {
+        self.similarity = similarity;
+        self
+    }
+    
+    /// Set the file `initialize` loads from and `persist` saves to
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+    
+    /// Load a store saved with `save`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::VectorStore(format!("{}: {}", path.display(), e)))?;
+        let file: StoreFile = serde_json::from_str(&contents)
+            .map_err(|e| Error::VectorStore(format!("{}: {}", path.display(), e)))?;
+        if file.version != FILE_VERSION {
+            return Err(Error::VectorStore(format!(
+                "{}: unsupported version {}",
+                path.display(),
+                file.version
+            )));
+        }
+        
+        let mut store = Self::new().with_similarity(file.similarity);
+        store.embedding_model = file.embedding_model;
+        store.check_embeddings(file.documents.iter().map(|document| &document.embedding))?;
+        store.documents.reserve(file.documents.len());
+        for document in file.documents {
+            store.upsert(document);
+        }
+        
+        // Continue numbering after the highest generated ID
+        store.next_id = store.documents.iter()
+            .filter_map(|document| document.id.strip_prefix("doc-")?.parse().ok())
+            .max()
+            .unwrap_or(0);
+        Ok(store)
+    }
+    
+    /// Write the store to a single JSON file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let file = StoreFile {
+            version: FILE_VERSION,
+            similarity: self.similarity,
+            embedding_model: self.embedding_model.clone(),
+            documents: self.documents.clone(),
+        };
+        let contents = serde_json::to_string(&file)?;
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| Error::VectorStore(format!("{}: {}", parent.display(), e)))?;
+        }
+        std::fs::write(path, contents)
+            .map_err(|e| Error::VectorStore(format!("{}: {}", path.display(), e)))?;
+        
+        info!("Saved {} documents to {}", self.documents.len(), path.display());
+        Ok(())
+    }
+    
+    /// Save the store to the path set with `with_path`
+    pub fn persist(&self) -> Result<()> {
+        match &self.path {
+            Some(path) => self.save(path),
+            None => Err(Error::VectorStore("no path set for the store".to_string())),
+        }
+    }
+    
+    /// Get the similarity measure
+    pub fn similarity(&self) -> Similarity {
+        self.similarity
+    }
+    
+    /// Get the model the stored embeddings came from, if known
+    pub fn embedding_model(&self) -> Option<&str> {
+        self.embedding_model.as_deref()
+    }
+    
+    /// Get the length of the stored vectors
+    pub fn dimensions(&self) -> Option<usize> {
+        self.documents.first().map(|document| document.embedding.len())
+    }
+    
+    /// Get the number of documents
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+    
+    /// Check if the store has no documents
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+    
+    /// Get all documents in insertion order
+    pub fn documents(&self) -> &[Document] {
+        &self.documents
+    }
+    
+    /// Get a document by ID
+    pub fn get(&self, id: &str) -> Option<&Document> {
+        self.index.get(id).map(|&position| &self.documents[position])
+    }
+    
+    /// Remove a document by ID
+    pub fn remove(&mut self, id: &str) -> Option<Document> {
+        let position = self.index.remove(id)?;
+        let document = self.documents.remove(position);
+        for later in &self.documents[position..] {
+            *self.index.get_mut(&later.id).expect("every document is indexed") -= 1;
+        }
+        Some(document)
+    }
+    
+    /// Remove every document
+    pub fn clear(&mut self) {
+        self.documents.clear();
+        self.index.clear();
+        self.embedding_model = None;
+    }
+    
+    /// Add a document with a precomputed embedding, replacing any with the same ID
+    pub fn insert(&mut self, document: Document) -> Result<()> {
+        self.check_dimensions(document.embedding.len())?;
+        self.upsert(document);
+        Ok(())
+    }
+    
+    /// Embed a text and add it, returning its generated ID
+    pub async fn add_text(
+        &mut self,
+        llm: &LLMInterface,
+        text: impl Into<String>,
+        metadata: Metadata,
+    ) -> Result<String> {
+        let mut ids = self.add_texts(llm, vec![(text.into(), metadata)]).await?;
+        Ok(ids.remove(0))
+    }
+    
+    /// Embed texts in as few requests as the provider allows and add them
+    pub async fn add_texts(
+        &mut self,
+        llm: &LLMInterface,
+        texts: Vec<(String, Metadata)>,
+    ) -> Result<Vec<String>> {
+        let model = self.check_model(llm)?;
+        let inputs: Vec<String> = texts.iter().map(|(text, _)| text.clone()).collect();
+        let embeddings = llm.embed(&inputs).await?;
+        
+        // Check the whole batch first so a bad vector adds nothing
+        if embeddings.len() != texts.len() {
+            return Err(Error::EmbeddingMismatch {
+                expected: texts.len(),
+                actual: embeddings.len(),
+            });
+        }
+        self.check_embeddings(&embeddings)?;
+        
+        let mut ids = Vec::with_capacity(texts.len());
+        for ((text, metadata), embedding) in texts.into_iter().zip(embeddings) {
+            let id = self.generate_id();
+            self.upsert(Document {
+                id: id.clone(),
+                text,
+                metadata,
+                embedding,
+            });
+            ids.push(id);
+        }
+        
+        self.embedding_model = Some(model);
+        info!("Added {} documents to the vector store", ids.len());
+        Ok(ids)
+    }
+    
+    /// Find the `k` documents most similar to a query vector
+    pub fn search(
+        &self,
+        query: &[f32],
+        k: usize,
+        filter: Option<&MetadataFilter>,
+    ) -> Result<Vec<SearchResult>> {
+        self.check_dimensions(query.len())?;
+        
+        let mut scored: Vec<(f32, &Document)> = self.documents.iter()
+            .filter(|document| filter.is_none_or(|filter| filter.matches(&document.metadata)))
+            .map(|document| (self.similarity.score(query, &document.embedding), document))
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        
+        Ok(scored.into_iter()
+            .take(k)
+            .map(|(score, document)| SearchResult {
+                document: document.clone(),
+                score,
+            })
+            .collect())
+    }
+    
+    /// Embed a query and find the `k` most similar documents
+    pub async fn search_text(
+        &self,
+        llm: &LLMInterface,
+        query: &str,
+        k: usize,
+        filter: Option<&MetadataFilter>,
+    ) -> Result<Vec<SearchResult>> {
+        if self.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        self.check_model(llm)?;
+        let embedding = llm.embed(&[query.to_string()]).await?.remove(0);
+        self.search(&embedding, k, filter)
+    }
+    
+    /// Check that a vector fits the ones already stored
+    fn check_dimensions(&self, actual: usize) -> Result<()> {
+        match self.dimensions() {
+            Some(expected) if expected != actual => Err(Error::DimensionMismatch { expected, actual }),
+            _ => Ok(()),
+        }
+    }
+    
+    /// Check that every vector has the length of the stored ones, or of the first
+    fn check_embeddings<'a>(&self, embeddings: impl IntoIterator<Item = &'a Vec<f32>>) -> Result<()> {
+        let mut expected = self.dimensions();
+        for embedding in embeddings {
+            match *expected.get_or_insert(embedding.len()) {
+                expected if expected != embedding.len() => {
+                    return Err(Error::DimensionMismatch {
+                        expected,
+                        actual: embedding.len(),
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+    
+    /// Add a document whose vector has been checked, replacing any with the same ID
+    fn upsert(&mut self, document: Document) {
+        match self.index.get(&document.id) {
+            Some(&position) => self.documents[position] = document,
+            None => {
+                self.index.insert(document.id.clone(), self.documents.len());
+                self.documents.push(document);
+            }
+        }
+    }
+    
+    /// Check that the interface embeds with the model the store was built with
+    fn check_model(&self, llm: &LLMInterface) -> Result<String> {
+        let model = llm.embedding_model().unwrap_or_default();
+        match &self.embedding_model {
+            Some(stored) if stored != model && !self.is_empty() => Err(Error::VectorStore(format!(
+                "store holds {} embeddings but the interface embeds with {}",
+                stored, model
+            ))),
+            _ => Ok(model.to_string()),
+        }
+    }
+    
+    /// Generate an ID no document has yet
+    fn generate_id(&mut self) -> String {
+        loop {
+            self.next_id += 1;
+            let id = format!("doc-{}", self.next_id);
+            if self.get(&id).is_none() {
+                return id;
+            }
+        }
+    }
+}
+
+impl Default for VectorStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NamedComponent for VectorStore {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+    
+    fn get_description(&self) -> &str {
+        &self.description
+    }
+}
+
+impl AIComponent for VectorStore {
+    fn initialize(&mut self) -> Result<()> {
+        match self.path.clone() {
+            Some(path) if path.exists() => {
+                let loaded = Self::load(&path)?;
+                *self = Self {
+                    path: Some(path.clone()),
+                    ..loaded
+                };
+                info!("Loaded {} documents from {}", self.documents.len(), path.display());
+            }
+            Some(path) => info!("No vector store at {}; starting empty", path.display()),
+            None => info!("Initializing in-memory vector store"),
+        }
+        Ok(())
+    }
+    
+    fn process(&self) -> Result<()> {
+        if self.is_empty() {
+            warn!("Vector store is empty");
+        }
+        info!(
+            "Vector store holds {} documents ({:?} dimensions)",
+            self.documents.len(),
+            self.dimensions()
+        );
+        Ok(())
+    }
+    
+    fn name(&self) -> &str {
+        self.get_name()
+    }
+    
+    fn description(&self) -> &str {
+        self.get_description()
+    }
+}
@@ -0,0 +1,164 @@
+//! Error types for the AI Playground library
+//! 
+//! Every fallible library call returns this crate's `Result`, so callers
+//! can branch on the kind of failure (authentication, rate limits,
+//! context length, ...) instead of matching on message strings.
+
+use crate::components::catalog::Feature;
+use crate::components::providers::ProviderKind;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Errors produced by AI Playground components
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The component was used before `initialize` was called
+    #[error("LLM Interface not initialized")]
+    NotInitialized,
+    
+    /// No API key is configured for a provider that requires one
+    #[error("API key not set")]
+    MissingApiKey,
+    
+    /// The API rejected the credentials (401 or 403)
+    #[error("Authentication failed with status {status}: {message}")]
+    Authentication { status: u16, message: String },
+    
+    /// The API rate limit was hit and retries were exhausted
+    #[error("Rate limited by the API: {message}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        message: String,
+    },
+    
+    /// The prompt plus requested output does not fit the model's context window
+    #[error("Context length exceeded: {message}")]
+    ContextLengthExceeded { message: String },
+    
+    /// Any other non-success HTTP response
+    #[error("API request failed with status {status}: {body}")]
+    Http { status: u16, body: String },
+    
+    /// The request could not be sent or the response could not be read
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    
+    /// The request did not complete in time
+    #[error("Request timed out")]
+    Timeout,
+    
+    /// A response body did not have the expected shape
+    #[error("Failed to parse API response: {0}")]
+    Deserialize(#[from] serde_json::Error),
+    
+    /// A streamed response reported an error or was malformed
+    #[error("Stream error: {0}")]
+    Stream(String),
+    
+    /// The API returned no usable content
+    #[error("API response contained no content")]
+    EmptyResponse,
+    
+    /// The model called a tool that is not registered
+    #[error("Unknown tool: {0}")]
+    UnknownTool(String),
+    
+    /// A tool handler failed
+    #[error("Tool {name} failed: {message}")]
+    Tool { name: String, message: String },
+    
+    /// The model kept calling tools without producing an answer
+    #[error("Model did not finish after {0} tool rounds")]
+    ToolRoundsExceeded(usize),
+    
+    /// A generated JSON schema could not be compiled
+    #[error("Invalid JSON schema for {name}: {message}")]
+    InvalidSchema { name: String, message: String },
+    
+    /// The model never produced output matching the requested schema
+    #[error("Model did not return valid {name} after {attempts} attempts: {message}")]
+    StructuredOutput {
+        name: String,
+        attempts: usize,
+        message: String,
+    },
+    
+    /// A prompt template was rejected
+    #[error("Invalid template: {0}")]
+    InvalidTemplate(String),
+    
+    /// A template placeholder has no value
+    #[error("Missing template variable: {0}")]
+    MissingVariable(String),
+    
+    /// The spending budget has been used up
+    #[error("Budget exceeded: spent ${spent:.4} of ${limit:.4}")]
+    BudgetExceeded { spent: f64, limit: f64 },
+    
+    /// The model is not in the catalog and no custom base URL is set
+    #[error("Unknown model: {0}")]
+    UnknownModel(String),
+    
+    /// The catalog lists the model under another provider
+    #[error("Model {model} is served by {expected:?}, not {provider:?}")]
+    ProviderMismatch { model: String, provider: ProviderKind, expected: ProviderKind },
+    
+    /// The request needs a capability the model does not have
+    #[error("Model {model} does not support {feature}")]
+    UnsupportedFeature { model: String, feature: Feature },
+    
+    /// An embeddings response did not have one vector per input
+    #[error("API returned {actual} embeddings for {expected} inputs")]
+    EmbeddingMismatch { expected: usize, actual: usize },
+    
+    /// A vector does not have the length of the ones already stored
+    #[error("Expected a {expected}-dimensional vector, got {actual} dimensions")]
+    DimensionMismatch { expected: usize, actual: usize },
+    
+    /// A vector store file could not be read or written, or was used with another model
+    #[error("Vector store error: {0}")]
+    VectorStore(String),
+    
+    /// A document could not be loaded
+    #[error("Document error: {0}")]
+    Document(String),
+    
+    /// The provider has no public endpoint and no base URL was set
+    #[error("{0} needs a base URL (the resource endpoint)")]
+    MissingBaseUrl(String),
+    
+    /// The provider has no support for an operation
+    #[error("{provider} does not support {operation}")]
+    Unsupported { provider: String, operation: String },
+    
+    /// A self-hosted server is running but does not have the model
+    #[error("Model {0} is not available on the server; pull it first (e.g. `ollama pull {0}`)")]
+    ModelNotPulled(String),
+    
+    /// A model catalog file could not be read or parsed
+    #[error("Invalid model catalog: {0}")]
+    InvalidCatalog(String),
+    
+    /// A cassette file could not be read or written
+    #[error("Cassette error: {0}")]
+    Cassette(String),
+    
+    /// A request in replay mode has no recorded response
+    #[error("No recorded response in cassette for {method} {url}")]
+    CassetteMiss { method: String, url: String },
+    
+    /// The response cache could not be read or written
+    #[error("Cache error: {0}")]
+    Cache(String),
+    
+    /// The mock provider has no scripted response for a request
+    #[error("Mock provider: {0}")]
+    Mock(String),
+    
+    /// No current prompt is set
+    #[error("No current prompt")]
+    NoCurrentPrompt,
+}
+
+/// Result type used throughout the library
+pub type Result<T> = std::result::Result<T, Error>;
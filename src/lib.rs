@@ -2,17 +2,21 @@
 //! 
 //! This library provides components for:
 //! - LLM interface management
+//! - Multi-turn conversations
 //! - Prompt template management
+//! - Document retrieval and question answering
 //! - AI component abstractions
 
 pub mod components;
+pub mod error;
 pub mod playground;
 
-pub use components::{AIComponent, LLMInterface, PromptManager};
+pub use components::{AIComponent, Conversation, LLMInterface, PromptManager, ProviderKind};
+pub use error::{Error, Result};
 pub use playground::AIPlayground;
 
 /// Re-export common types and traits
 pub mod prelude {
-    pub use crate::components::{AIComponent, LLMInterface, PromptManager};
+    pub use crate::components::{AIComponent, Conversation, LLMInterface, PromptManager, ProviderKind};
     pub use crate::playground::AIPlayground;
 } 
\ No newline at end of file
@@ -1,7 +1,6 @@
 use ai_playground::AIPlayground;
 use anyhow::Result;
 use tracing::{error, info};
-use tracing_subscriber;
 
 #[tokio::main]
 async fn main() -> Result<()> {
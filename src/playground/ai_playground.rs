@@ -1,23 +1,91 @@
-use crate::components::{AIComponent, LLMInterface, PromptManager};
+use crate::components::rag::source_label;
+use crate::components::{
+    AIComponent, Budget, Chunker, DocumentLoader, LLMInterface, PromptManager, RagPipeline,
+    ResponseCache, VectorStore,
+};
 use anyhow::Result;
+use futures::StreamExt;
 use std::io::{self, Write};
-use tracing::{info, error};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, error, warn};
+
+/// Directory of the response cache under the data directory
+const CACHE_DIR: &str = "ai-playground-cache";
+
+/// File of the document index under the data directory
+const DOCUMENT_INDEX: &str = "ai-playground-documents.json";
+
+/// Environment variable naming the data directory; the user's data directory when unset
+const DATA_DIR_VAR: &str = "AI_PLAYGROUND_DATA_DIR";
+
+/// Name of the data directory under the user's data directory
+const APP_DIR: &str = "ai-playground";
+
+/// Chunk size and overlap used when indexing documents, in tokens
+const CHUNK_TOKENS: usize = 400;
+const CHUNK_OVERLAP_TOKENS: usize = 50;
+
+/// Pick the data directory from the environment
+fn default_data_dir() -> PathBuf {
+    let var = |name| std::env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+    if let Some(dir) = var(DATA_DIR_VAR) {
+        return dir;
+    }
+    
+    var("XDG_DATA_HOME")
+        .or_else(|| var("HOME").map(|home| home.join(".local").join("share")))
+        .or_else(|| var("LOCALAPPDATA"))
+        .unwrap_or_default()
+        .join(APP_DIR)
+}
 
 /// Main AI Playground class that orchestrates all components
 pub struct AIPlayground {
     pub llm_interface: LLMInterface,
     pub prompt_manager: PromptManager,
+    /// Spend across every call made in this session
+    pub budget: Arc<Budget>,
+    /// Response cache, opened on initialization
+    pub cache: Option<Arc<ResponseCache>>,
+    /// Question answering over indexed documents
+    pub rag: RagPipeline,
+    /// Directory holding the response cache and document index
+    data_dir: PathBuf,
 }
 
 impl AIPlayground {
     /// Create a new AI Playground instance
+    /// 
+    /// Session data is kept in the directory named by `AI_PLAYGROUND_DATA_DIR`,
+    /// or in `ai-playground` under `$XDG_DATA_HOME` (`~/.local/share` by
+    /// default), so it is never shared with other users.
     pub fn new() -> Self {
+        Self::with_data_dir(default_data_dir())
+    }
+    
+    /// Create a new AI Playground instance keeping session data in `data_dir`
+    pub fn with_data_dir(data_dir: impl Into<PathBuf>) -> Self {
+        let data_dir = data_dir.into();
+        let budget = Arc::new(Budget::unlimited());
+        let mut llm_interface = LLMInterface::new(None);
+        llm_interface.set_budget(budget.clone());
+        
         Self {
-            llm_interface: LLMInterface::new(None),
+            llm_interface,
             prompt_manager: PromptManager::new(),
+            budget,
+            cache: None,
+            rag: RagPipeline::new(VectorStore::new().with_path(data_dir.join(DOCUMENT_INDEX))),
+            data_dir,
         }
     }
     
+    /// Get the directory holding the response cache and document index
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+    
     /// Initialize all components
     pub fn initialize(&mut self) -> Result<()> {
         info!("=== AI Playground Initialization ===");
@@ -25,6 +93,22 @@ impl AIPlayground {
         self.llm_interface.initialize()?;
         self.prompt_manager.initialize()?;
         
+        if let Err(e) = self.rag.initialize() {
+            warn!("Document index not loaded: {}", e);
+        }
+        
+        if self.cache.is_none() {
+            let path = self.data_dir.join(CACHE_DIR);
+            match ResponseCache::open(&path) {
+                Ok(cache) => {
+                    let cache = Arc::new(cache);
+                    self.llm_interface.set_cache(cache.clone());
+                    self.cache = Some(cache);
+                }
+                Err(e) => warn!("Response cache disabled: {}", e),
+            }
+        }
+        
         info!("Initialization complete!");
         Ok(())
     }
@@ -55,6 +139,7 @@ impl AIPlayground {
         println!("\n3. Component Information:");
         println!("- {}: {}", self.llm_interface.name(), self.llm_interface.description());
         println!("- {}: {}", self.prompt_manager.name(), self.prompt_manager.description());
+        println!("- {}: {}", self.rag.name(), self.rag.description());
         
         println!("\nDemo complete!");
         Ok(())
@@ -63,17 +148,32 @@ impl AIPlayground {
     /// Show the interactive menu
     fn show_menu(&self) {
         println!("\n=== AI Playground Menu ===");
+        println!("{}", self.spend_summary());
+        if let Some(cache) = &self.cache {
+            println!("{}", cache_summary(cache));
+        }
         println!("1. Initialize components");
         println!("2. Run demo");
         println!("3. Set API key");
         println!("4. Add prompt template");
         println!("5. List all templates");
         println!("6. Test LLM response");
-        println!("7. Exit");
+        println!("7. Set spending budget");
+        println!("8. Toggle forced caching");
+        println!("9. Ask your documents");
+        println!("10. Exit");
         print!("Choose an option: ");
         io::stdout().flush().unwrap();
     }
     
+    /// Describe the session spend and budget
+    fn spend_summary(&self) -> String {
+        match self.budget.limit() {
+            Some(limit) => format!("Session spend: ${:.4} of ${:.2} budget", self.budget.spent(), limit),
+            None => format!("Session spend: ${:.4}", self.budget.spent()),
+        }
+    }
+    
     /// Get user input
     fn get_input(&self) -> String {
         let mut input = String::new();
@@ -114,9 +214,109 @@ impl AIPlayground {
         io::stdout().flush().unwrap();
         let prompt = self.get_input();
         
-        match self.llm_interface.generate_response(&prompt).await {
-            Ok(response) => println!("Response: {}", response),
-            Err(e) => println!("Error: {}", e),
+        let mut stream = match self.llm_interface.stream_response(&prompt).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                println!("Error: {}", e);
+                return Ok(());
+            }
+        };
+        
+        // Print tokens as they arrive
+        print!("Response: ");
+        io::stdout().flush()?;
+        while let Some(delta) = stream.next().await {
+            match delta {
+                Ok(text) => {
+                    print!("{}", text);
+                    io::stdout().flush()?;
+                }
+                Err(e) => {
+                    println!("\nError: {}", e);
+                    return Ok(());
+                }
+            }
+        }
+        println!();
+        Ok(())
+    }
+    
+    /// Handle menu option 7: Set spending budget
+    fn handle_set_budget(&mut self) {
+        print!("Enter budget in USD (empty for no limit): ");
+        io::stdout().flush().unwrap();
+        let input = self.get_input();
+        
+        if input.is_empty() {
+            self.budget.set_limit(None);
+            println!("Budget removed");
+            return;
+        }
+        
+        match input.trim_start_matches('$').parse::<f64>() {
+            Ok(limit) if limit >= 0.0 => {
+                self.budget.set_limit(Some(limit));
+                println!("Budget set to ${:.2}", limit);
+            }
+            _ => println!("Invalid amount: {}", input),
+        }
+    }
+    
+    /// Handle menu option 8: Toggle forced caching
+    fn handle_toggle_caching(&self) {
+        let Some(cache) = &self.cache else {
+            println!("Initialize components to open the response cache");
+            return;
+        };
+        
+        cache.set_forced(!cache.is_forced());
+        if cache.is_forced() {
+            println!("Caching forced for every request");
+        } else {
+            println!("Caching only requests with temperature 0");
+        }
+    }
+    
+    /// Handle menu option 9: Ask your documents
+    async fn handle_ask_documents(&mut self) -> Result<()> {
+        let indexed = self.rag.store().len();
+        if indexed == 0 {
+            print!("Directory of documents to index: ");
+        } else {
+            print!("Directory of documents to index (empty to use the {} indexed chunks): ", indexed);
+        }
+        io::stdout().flush()?;
+        let dir = self.get_input();
+        
+        if !dir.is_empty() {
+            let documents = DocumentLoader::new().load_dir(&dir)?;
+            let chunks = Chunker::for_interface(&self.llm_interface, CHUNK_TOKENS, CHUNK_OVERLAP_TOKENS)
+                .chunk_all(&documents);
+            
+            self.rag.store_mut().clear();
+            let count = self.rag.index(&self.llm_interface, &chunks).await?;
+            self.rag.store().persist()?;
+            println!("Indexed {} chunks from {} documents", count, documents.len());
+        } else if indexed == 0 {
+            println!("No documents indexed");
+            return Ok(());
+        }
+        
+        print!("Question: ");
+        io::stdout().flush()?;
+        let question = self.get_input();
+        if question.is_empty() {
+            return Ok(());
+        }
+        
+        let answer = self.rag.ask(&self.llm_interface, &question).await?;
+        println!("\n{}", answer.answer);
+        if !answer.cited.is_empty() {
+            println!("\nSources:");
+            for result in &answer.cited {
+                let number = answer.retrieved.iter().position(|retrieved| retrieved == result).unwrap_or(0) + 1;
+                println!("[{}] {} (score {:.3})", number, source_label(&result.document), result.score);
+            }
         }
         Ok(())
     }
@@ -135,6 +335,8 @@ impl AIPlayground {
                 "1" => {
                     if let Err(e) = self.initialize() {
                         error!("Initialization failed: {}", e);
+                    } else if let Err(e) = self.llm_interface.verify().await {
+                        error!("Model check failed: {}", e);
                     }
                 }
                 "2" => {
@@ -150,7 +352,15 @@ impl AIPlayground {
                         error!("LLM test failed: {}", e);
                     }
                 }
-                "7" => {
+                "7" => self.handle_set_budget(),
+                "8" => self.handle_toggle_caching(),
+                "9" => {
+                    if let Err(e) = self.handle_ask_documents().await {
+                        error!("Document question failed: {}", e);
+                    }
+                }
+                "10" => {
+                    println!("{}", self.spend_summary());
                     println!("Goodbye!");
                     break;
                 }
@@ -162,6 +372,12 @@ impl AIPlayground {
     }
 }
 
+/// Describe the cache hit and miss counts
+fn cache_summary(cache: &ResponseCache) -> String {
+    let forced = if cache.is_forced() { ", forced" } else { "" };
+    format!("Cache: {} hits, {} misses{}", cache.hits(), cache.misses(), forced)
+}
+
 impl Default for AIPlayground {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,108 @@
+use ai_playground::components::{LLMConfig, ProviderKind};
+use ai_playground::prelude::*;
+use ai_playground::Error;
+use anyhow::Result;
+use futures::StreamExt;
+use serde_json::json;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn azure_config(server: &MockServer) -> LLMConfig {
+    let mut config = LLMConfig::azure(server.uri(), "prod-chat", "gpt-4o-mini");
+    config.api_key = Some("azure-key".to_string());
+    config.max_tokens = Some(100_000);
+    config
+}
+
+#[tokio::test]
+async fn test_chat_goes_to_deployment_with_api_key() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/openai/deployments/prod-chat/chat/completions"))
+        .and(query_param("api-version", "2024-10-21"))
+        .and(header("api-key", "azure-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "chatcmpl-1",
+            "model": "gpt-4o-mini",
+            "choices": [{
+                "message": {"role": "assistant", "content": "Hello from Azure"},
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 9, "completion_tokens": 4, "total_tokens": 13}
+        })))
+        .mount(&server)
+        .await;
+    
+    let mut llm = LLMInterface::with_config(azure_config(&server));
+    llm.initialize()?;
+    assert_eq!(llm.provider_name(), "Azure OpenAI");
+    
+    // The catalog still knows the model behind the deployment
+    assert_eq!(llm.config().max_tokens, Some(16384));
+    assert_eq!(llm.model_info().unwrap().context_window, 128000);
+    
+    let completion = llm.generate("Hi").await?;
+    assert_eq!(completion.text(), "Hello from Azure");
+    assert!(completion.cost.unwrap() > 0.0);
+    
+    let requests = server.received_requests().await.unwrap();
+    assert!(!requests[0].headers.contains_key("authorization"));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_streaming_and_api_version_override() -> Result<()> {
+    let server = MockServer::start().await;
+    let body = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+        "data: {\"choices\":[]}\n\n",
+        "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n",
+        "data: [DONE]\n\n",
+    );
+    Mock::given(method("POST"))
+        .and(path("/openai/deployments/gpt-4o-mini/chat/completions"))
+        .and(query_param("api-version", "2025-01-01-preview"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .mount(&server)
+        .await;
+    
+    // Without a deployment name the model name is used
+    let mut config = azure_config(&server);
+    config.azure.deployment = None;
+    config.azure.api_version = Some("2025-01-01-preview".to_string());
+    let mut llm = LLMInterface::with_config(config);
+    llm.initialize()?;
+    
+    let chunks: Vec<String> = llm.stream_response("Hi").await?
+        .map(|chunk| chunk.unwrap())
+        .collect()
+        .await;
+    assert_eq!(chunks.concat(), "Hello");
+    
+    Ok(())
+}
+
+#[test]
+fn test_azure_needs_an_endpoint() {
+    let mut config = LLMConfig::new("gpt-4o".to_string());
+    config.provider = ProviderKind::Azure;
+    let mut llm = LLMInterface::with_config(config);
+    
+    assert!(matches!(llm.initialize(), Err(Error::MissingBaseUrl(_))));
+}
+
+#[tokio::test]
+async fn test_azure_needs_an_api_key() -> Result<()> {
+    let server = MockServer::start().await;
+    let mut config = azure_config(&server);
+    config.api_key = None;
+    let mut llm = LLMInterface::with_config(config);
+    llm.initialize()?;
+    
+    // A resource endpoint is not a self-hosted server
+    assert!(matches!(llm.generate("Hi").await, Err(Error::MissingApiKey)));
+    assert!(server.received_requests().await.unwrap().is_empty());
+    
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use ai_playground::components::{Feature, Image, LLMConfig, Message, MockProvider, MockResponse, ModelCatalog, ResponseCache};
+use ai_playground::prelude::*;
+use ai_playground::Error;
+use anyhow::Result;
+use futures::StreamExt;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn cached_llm(mock: &MockProvider, cache: &Arc<ResponseCache>, temperature: f32) -> Result<LLMInterface> {
+    let mut config = LLMConfig::new("mock-model".to_string());
+    config.temperature = Some(temperature);
+    let mut llm = LLMInterface::with_provider(config, mock.clone());
+    llm.set_cache(cache.clone());
+    llm.initialize()?;
+    Ok(llm)
+}
+
+#[tokio::test]
+async fn test_deterministic_requests_are_cached() -> Result<()> {
+    let mock = MockProvider::new().reply("Paris").reply("Berlin");
+    let cache = Arc::new(ResponseCache::temporary()?);
+    let llm = cached_llm(&mock, &cache, 0.0)?;
+    
+    let first = llm.generate("Capital of France?").await?;
+    let second = llm.generate("Capital of France?").await?;
+    assert_eq!(first.text(), "Paris");
+    assert_eq!(second.text(), "Paris");
+    assert_eq!(second.cost, Some(0.0));
+    assert_eq!(mock.request_count(), 1);
+    
+    // A different prompt is a different key
+    assert_eq!(llm.generate_response("Capital of Germany?").await?, "Berlin");
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 2, 2));
+    
+    // So are different provider options
+    let mut config = LLMConfig::new("mock-model".to_string());
+    config.temperature = Some(0.0);
+    config.ollama.seed = Some(7);
+    let mut seeded = LLMInterface::with_provider(config, mock.clone());
+    seeded.set_cache(cache.clone());
+    seeded.initialize()?;
+    mock.enqueue(MockResponse::Text("Paris, seeded".to_string()));
+    assert_eq!(seeded.generate_response("Capital of France?").await?, "Paris, seeded");
+    assert_eq!(cache.len(), 3);
+    
+    // Streaming reuses the stored reply
+    let text: Vec<String> = llm.stream_response("Capital of France?").await?
+        .map(|chunk| chunk.unwrap())
+        .collect()
+        .await;
+    assert_eq!(text.concat(), "Paris");
+    assert_eq!(mock.request_count(), 3);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sampled_requests_skip_cache_unless_forced() -> Result<()> {
+    let mock = MockProvider::new().default_reply("Hello");
+    let cache = Arc::new(ResponseCache::temporary()?);
+    let llm = cached_llm(&mock, &cache, 0.7)?;
+    
+    llm.generate_response("Hi").await?;
+    llm.generate_response("Hi").await?;
+    assert_eq!(mock.request_count(), 2);
+    assert!(cache.is_empty());
+    
+    cache.set_forced(true);
+    llm.generate_response("Hi").await?;
+    llm.generate_response("Hi").await?;
+    assert_eq!(mock.request_count(), 3);
+    assert_eq!(cache.hits(), 1);
+    
+    // Bypass wins over forcing
+    cache.set_bypass(true);
+    llm.generate_response("Hi").await?;
+    assert_eq!(mock.request_count(), 4);
+    assert_eq!(cache.hits(), 1);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ttl_and_size_limit() -> Result<()> {
+    let mock = MockProvider::new().default_reply("Reply");
+    
+    let expiring = Arc::new(ResponseCache::temporary()?.with_ttl(Duration::ZERO));
+    let llm = cached_llm(&mock, &expiring, 0.0)?;
+    llm.generate_response("Question").await?;
+    llm.generate_response("Question").await?;
+    assert_eq!(mock.request_count(), 2);
+    assert_eq!(expiring.hits(), 0);
+    
+    let small = Arc::new(ResponseCache::temporary()?.with_max_entries(2));
+    let llm = cached_llm(&mock, &small, 0.0)?;
+    for prompt in ["One", "Two", "Three", "Four"] {
+        llm.generate_response(prompt).await?;
+    }
+    assert_eq!(small.len(), 2);
+    
+    // The two newest entries are kept
+    let requests = mock.request_count();
+    llm.generate_response("Four").await?;
+    llm.generate_response("Three").await?;
+    assert_eq!(mock.request_count(), requests);
+    
+    small.clear()?;
+    assert!(small.is_empty());
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cache_hits_still_check_model_features() -> Result<()> {
+    let mock = MockProvider::new().default_reply("A cat");
+    let cache = Arc::new(ResponseCache::temporary()?);
+    let mut llm = cached_llm(&mock, &cache, 0.0)?;
+    let picture = || {
+        let mut conversation = Conversation::new();
+        conversation.push(Message::user("Describe this").with_image(Image::url("https://example.com/cat.png")));
+        conversation
+    };
+    assert_eq!(llm.chat(&mut picture()).await?, "A cat");
+    
+    llm.set_catalog(ModelCatalog::from_toml_str(r#"
+        [models."mock-model"]
+        provider = "openai"
+        context_window = 4096
+        max_output_tokens = 1024
+        features = []
+    "#)?);
+    let result = llm.chat(&mut picture()).await;
+    assert!(matches!(result, Err(Error::UnsupportedFeature { feature: Feature::Vision, .. })));
+    assert_eq!((cache.hits(), mock.request_count()), (0, 1));
+    
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use ai_playground::components::{Cassette, CassetteMode, LLMConfig};
+use ai_playground::prelude::*;
+use ai_playground::Error;
+use anyhow::Result;
+use futures::StreamExt;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const API_KEY: &str = "sk-test-secret-key";
+
+/// Address nothing listens on, so replayed calls cannot reach a server
+const UNREACHABLE: &str = "http://127.0.0.1:9/v1";
+
+fn cassette_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("ai-playground-{}-{}.json", name, std::process::id()))
+}
+
+fn llm_with_cassette(model: &str, base_url: &str, cassette: Arc<Cassette>) -> Result<LLMInterface> {
+    let mut config = LLMConfig::new(model.to_string());
+    config.base_url = Some(base_url.to_string());
+    let mut llm = LLMInterface::with_config(config);
+    llm.set_cassette(cassette);
+    llm.initialize()?;
+    Ok(llm)
+}
+
+#[tokio::test]
+async fn test_record_then_replay_without_network() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "chatcmpl-recorded",
+            "choices": [{"message": {"role": "assistant", "content": "Recorded reply"}, "finish_reason": "stop"}]
+        })))
+        .mount(&server)
+        .await;
+    
+    let file = cassette_path("record-replay");
+    let cassette = Arc::new(Cassette::record(&file));
+    let mut llm = llm_with_cassette("gpt-4o-mini", &format!("{}/v1", server.uri()), cassette.clone())?;
+    llm.set_api_key(API_KEY.to_string());
+    assert_eq!(llm.generate_response("Hello").await?, "Recorded reply");
+    assert_eq!(cassette.len(), 1);
+    
+    // Credentials never reach the file
+    let contents = std::fs::read_to_string(&file)?;
+    assert!(!contents.contains(API_KEY));
+    assert!(contents.contains("[REDACTED]"));
+    
+    let cassette = Arc::new(Cassette::replay(&file)?);
+    assert_eq!(cassette.mode(), CassetteMode::Replay);
+    let replay = llm_with_cassette("gpt-4o-mini", UNREACHABLE, cassette)?;
+    let completion = replay.generate("Hello").await?;
+    assert_eq!(completion.text(), "Recorded reply");
+    assert_eq!(completion.id.as_deref(), Some("chatcmpl-recorded"));
+    
+    // Anything not on the cassette fails loudly
+    assert!(matches!(replay.generate("Something else").await, Err(Error::CassetteMiss { .. })));
+    
+    std::fs::remove_file(&file)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replay_streams_and_errors() -> Result<()> {
+    let server = MockServer::start().await;
+    let events = concat!(
+        "event: content_block_delta\n",
+        "data: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"Hi \"}}\n\n",
+        "event: content_block_delta\n",
+        "data: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"there\"}}\n\n",
+        "event: message_stop\n",
+        "data: {\"type\":\"message_stop\"}\n\n",
+    );
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(events, "text/event-stream"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({
+            "type": "error",
+            "error": {"type": "authentication_error", "message": "invalid x-api-key"}
+        })))
+        .mount(&server)
+        .await;
+    
+    let file = cassette_path("stream");
+    let cassette = Arc::new(Cassette::record(&file));
+    let llm = llm_with_cassette("claude-3-5-haiku-latest", &format!("{}/v1", server.uri()), cassette)?;
+    let recorded: Vec<String> = llm.stream_response("Greet me").await?.map(|d| d.unwrap()).collect().await;
+    assert_eq!(recorded.concat(), "Hi there");
+    assert!(llm.generate("Greet me").await.is_err());
+    
+    let cassette = Arc::new(Cassette::replay(&file)?);
+    let replay = llm_with_cassette("claude-3-5-haiku-latest", UNREACHABLE, cassette)?;
+    let replayed: Vec<String> = replay.stream_response("Greet me").await?.map(|d| d.unwrap()).collect().await;
+    assert_eq!(replayed.concat(), "Hi there");
+    assert!(matches!(
+        replay.generate("Greet me").await,
+        Err(Error::Authentication { status: 401, .. })
+    ));
+    
+    std::fs::remove_file(&file)?;
+    Ok(())
+}
+
+#[test]
+fn test_missing_cassette_file() {
+    assert!(matches!(
+        Cassette::replay(cassette_path("does-not-exist")),
+        Err(Error::Cassette(_))
+    ));
+}
@@ -0,0 +1,147 @@
+use ai_playground::components::{Feature, Image, LLMConfig, Message, ModelCatalog, ModelPrice, Usage};
+use ai_playground::prelude::*;
+use ai_playground::Error;
+use anyhow::Result;
+use serde_json::{json, Value};
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const CUSTOM_CATALOG: &str = r#"
+[models."acme-large"]
+provider = "anthropic"
+context_window = 32000
+max_output_tokens = 2048
+features = ["streaming"]
+price = { input = 1.0, output = 4.0 }
+"#;
+
+#[test]
+fn test_built_in_catalog_lookup() {
+    let catalog = ModelCatalog::built_in();
+    
+    let info = catalog.get("gpt-4o-2024-08-06").unwrap();
+    assert_eq!(info.provider, ProviderKind::OpenAI);
+    assert_eq!(info.context_window, 128000);
+    assert!(info.supports(Feature::Json));
+    
+    let info = catalog.get("claude-3-5-haiku-latest").unwrap();
+    assert_eq!(info.provider, ProviderKind::Anthropic);
+    assert_eq!(info.max_output_tokens, 8192);
+    
+    assert!(catalog.get("not-a-model").is_none());
+    
+    // Names only fall back to their family at a `-`
+    assert!(catalog.get("gpt-4.5-preview").is_none());
+    assert_eq!(catalog.get("gpt-4o-mini-2024-07-18"), catalog.get("gpt-4o-mini"));
+}
+
+#[test]
+fn test_catalog_loads_and_extends_from_toml() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("ai-playground-catalog-{}.toml", std::process::id()));
+    std::fs::write(&path, CUSTOM_CATALOG)?;
+    
+    let mut catalog = ModelCatalog::built_in().clone();
+    catalog.extend_from_file(&path)?;
+    std::fs::remove_file(&path)?;
+    
+    let info = catalog.get("acme-large-v2").unwrap();
+    assert_eq!(info.provider, ProviderKind::Anthropic);
+    assert_eq!(info.price, Some(ModelPrice::new(1.0, 4.0)));
+    assert!(catalog.get("gpt-4o").is_some());
+    
+    let usage = Usage {
+        prompt_tokens: 1_000_000,
+        completion_tokens: 0,
+        total_tokens: 1_000_000,
+        cached_tokens: 0,
+    };
+    assert_eq!(catalog.price_table().cost("acme-large", &usage), Some(1.0));
+    
+    assert!(matches!(
+        ModelCatalog::from_toml_str("[models.broken]\nprovider = 3"),
+        Err(Error::InvalidCatalog(_))
+    ));
+    
+    Ok(())
+}
+
+#[test]
+fn test_initialize_validates_model_and_clamps_max_tokens() -> Result<()> {
+    let mut llm = LLMInterface::new(Some("gpt-17-ultra".to_string()));
+    assert!(matches!(llm.initialize(), Err(Error::UnknownModel(model)) if model == "gpt-17-ultra"));
+    
+    let mut config = LLMConfig::new("gpt-3.5-turbo".to_string());
+    config.max_tokens = Some(100_000);
+    let mut llm = LLMInterface::with_config(config);
+    llm.initialize()?;
+    assert_eq!(llm.config().max_tokens, Some(4096));
+    
+    let mut llm = LLMInterface::new(Some("acme-large".to_string()));
+    let mut catalog = ModelCatalog::empty();
+    catalog.extend(ModelCatalog::from_toml_str(CUSTOM_CATALOG)?);
+    llm.set_catalog(catalog);
+    // The catalog lists acme-large under Anthropic
+    assert!(matches!(
+        llm.initialize(),
+        Err(Error::ProviderMismatch { provider: ProviderKind::OpenAI, expected: ProviderKind::Anthropic, .. })
+    ));
+    llm.set_provider(ProviderKind::Anthropic);
+    llm.initialize()?;
+    assert_eq!(llm.model_info().unwrap().context_window, 32000);
+    assert_eq!(llm.config().max_tokens, Some(1000));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unsupported_features_fail_before_sending() -> Result<()> {
+    let server = MockServer::start().await;
+    
+    let mut llm = LLMInterface::new(Some("gpt-3.5-turbo".to_string()));
+    llm.initialize()?;
+    llm.set_base_url(format!("{}/v1", server.uri()));
+    
+    let result = llm.generate_structured::<Value>("What is 2 + 2?").await;
+    assert!(matches!(
+        result,
+        Err(Error::UnsupportedFeature { feature: Feature::Json, .. })
+    ));
+    
+    let mut conversation = Conversation::new();
+    conversation.push(Message::user("What is in this picture?").with_image(Image::url("https://example.com/cat.png")));
+    assert!(matches!(
+        llm.chat(&mut conversation).await,
+        Err(Error::UnsupportedFeature { feature: Feature::Vision, .. })
+    ));
+    assert!(server.received_requests().await.unwrap().is_empty());
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reasoning_models_get_max_completion_tokens() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(json!({"model": "o3-mini", "max_completion_tokens": 1000})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{"message": {"role": "assistant", "content": "4"}}]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    
+    let mut llm = LLMInterface::new(Some("o3-mini".to_string()));
+    llm.initialize()?;
+    llm.set_base_url(format!("{}/v1", server.uri()));
+    assert!(llm.model_info().unwrap().reasoning);
+    assert_eq!(llm.generate_response("What is 2 + 2?").await?, "4");
+    
+    // The o-series rejects both settings
+    let requests = server.received_requests().await.unwrap();
+    let body: Value = serde_json::from_slice(&requests[0].body)?;
+    assert!(body.get("max_tokens").is_none());
+    assert!(body.get("temperature").is_none());
+    
+    Ok(())
+}
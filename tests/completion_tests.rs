@@ -0,0 +1,101 @@
+use ai_playground::components::{FinishReason, Usage};
+use ai_playground::prelude::*;
+use anyhow::Result;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn local_llm(server: &MockServer, model: &str) -> Result<LLMInterface> {
+    let mut llm = LLMInterface::new(Some(model.to_string()));
+    llm.initialize()?;
+    llm.set_base_url(format!("{}/v1", server.uri()));
+    Ok(llm)
+}
+
+#[tokio::test]
+async fn test_openai_completion_metadata() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "chatcmpl-123",
+            "model": "gpt-4o-mini-2024-07-18",
+            "choices": [{
+                "message": {"role": "assistant", "content": "Once upon a"},
+                "finish_reason": "length"
+            }],
+            "usage": {
+                "prompt_tokens": 120,
+                "completion_tokens": 3,
+                "total_tokens": 123,
+                "prompt_tokens_details": {"cached_tokens": 100}
+            }
+        })))
+        .mount(&server)
+        .await;
+    
+    let llm = local_llm(&server, "gpt-4o-mini").await?;
+    let completion = llm.generate("Tell me a story").await?;
+    
+    assert_eq!(completion.text(), "Once upon a");
+    assert_eq!(completion.id.as_deref(), Some("chatcmpl-123"));
+    assert_eq!(completion.model.as_deref(), Some("gpt-4o-mini-2024-07-18"));
+    assert!(completion.is_truncated());
+    assert_eq!(completion.usage, Some(Usage {
+        prompt_tokens: 120,
+        completion_tokens: 3,
+        total_tokens: 123,
+        cached_tokens: 100,
+    }));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_anthropic_completion_metadata() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "msg_01",
+            "model": "claude-3-5-haiku-20241022",
+            "content": [{"type": "text", "text": "Done."}],
+            "stop_reason": "end_turn",
+            "usage": {
+                "input_tokens": 10,
+                "output_tokens": 2,
+                "cache_read_input_tokens": 50,
+                "cache_creation_input_tokens": 0
+            }
+        })))
+        .mount(&server)
+        .await;
+    
+    let llm = local_llm(&server, "claude-3-5-haiku-latest").await?;
+    let mut conversation = Conversation::new();
+    conversation.add_user("Finish up");
+    let completion = llm.complete(&mut conversation).await?;
+    
+    assert_eq!(completion.finish_reason, Some(FinishReason::Stop));
+    assert_eq!(completion.model.as_deref(), Some("claude-3-5-haiku-20241022"));
+    let usage = completion.usage.unwrap();
+    assert_eq!(usage.prompt_tokens, 60);
+    assert_eq!(usage.cached_tokens, 50);
+    assert_eq!(usage.total_tokens, 62);
+    assert_eq!(conversation.last_reply(), Some("Done."));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_finish_reasons_are_normalized() -> Result<()> {
+    assert_eq!(FinishReason::from_provider("max_tokens"), FinishReason::Length);
+    assert_eq!(FinishReason::from_provider("tool_use"), FinishReason::ToolCalls);
+    assert_eq!(FinishReason::from_provider("stop"), FinishReason::Stop);
+    assert_eq!(
+        FinishReason::from_provider("pause_turn"),
+        FinishReason::Other("pause_turn".to_string())
+    );
+    
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use ai_playground::components::{Budget, LLMConfig, MockProvider, ModelPrice, PriceTable, Usage};
+use ai_playground::prelude::*;
+use ai_playground::Error;
+use anyhow::Result;
+use futures::StreamExt;
+use serde_json::json;
+use std::sync::Arc;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Mount a completion that reports one million prompt and completion tokens
+async fn mount_priced_reply(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{"message": {"role": "assistant", "content": "Hi"}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 1_000_000, "completion_tokens": 1_000_000, "total_tokens": 2_000_000}
+        })))
+        .mount(server)
+        .await;
+}
+
+fn local_llm(server: &MockServer) -> Result<LLMInterface> {
+    let mut llm = LLMInterface::new(Some("gpt-4o-mini".to_string()));
+    llm.initialize()?;
+    llm.set_base_url(format!("{}/v1", server.uri()));
+    Ok(llm)
+}
+
+#[test]
+fn test_price_lookup_and_cost() {
+    let prices = PriceTable::default();
+    
+    // Dated snapshots use the longest matching family
+    assert_eq!(prices.get("gpt-4o-mini-2024-07-18"), prices.get("gpt-4o-mini"));
+    assert_ne!(prices.get("gpt-4o-2024-08-06"), prices.get("gpt-4"));
+    assert!(prices.get("my-local-model").is_none());
+    
+    let price = ModelPrice::new(2.0, 8.0).with_cached_input(0.5);
+    let usage = Usage {
+        prompt_tokens: 1_000_000,
+        completion_tokens: 500_000,
+        total_tokens: 1_500_000,
+        cached_tokens: 400_000,
+    };
+    // 600k uncached * $2 + 400k cached * $0.5 + 500k output * $8
+    assert!((price.cost(&usage) - 5.4).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_cost_is_accumulated() -> Result<()> {
+    let server = MockServer::start().await;
+    mount_priced_reply(&server).await;
+    
+    let mut llm = local_llm(&server)?;
+    llm.set_price("gpt-4o-mini", ModelPrice::new(1.0, 2.0));
+    
+    let completion = llm.generate("Hello").await?;
+    assert_eq!(completion.cost, Some(3.0));
+    llm.generate("Hello again").await?;
+    assert_eq!(llm.total_cost(), 6.0);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_budget_refuses_calls_once_exceeded() -> Result<()> {
+    let server = MockServer::start().await;
+    mount_priced_reply(&server).await;
+    
+    let budget = Arc::new(Budget::new(Some(5.0)));
+    let mut first = local_llm(&server)?;
+    let mut second = local_llm(&server)?;
+    for llm in [&mut first, &mut second] {
+        llm.set_price("gpt-4o-mini", ModelPrice::new(1.0, 2.0));
+        llm.set_budget(budget.clone());
+    }
+    
+    first.generate("One").await?;
+    second.generate("Two").await?;
+    assert_eq!(budget.spent(), 6.0);
+    assert_eq!(budget.calls(), 2);
+    
+    // The shared budget is spent, so neither interface sends another request
+    let result = first.generate("Three").await;
+    assert!(matches!(result, Err(Error::BudgetExceeded { limit, .. }) if limit == 5.0));
+    assert!(second.stream_response("Four").await.is_err());
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    
+    budget.set_limit(Some(10.0));
+    assert_eq!(budget.remaining(), Some(4.0));
+    assert!(first.generate("Five").await.is_ok());
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_streamed_replies_are_charged() -> Result<()> {
+    let server = MockServer::start().await;
+    let body = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"Hi\"}}]}\n\n",
+        "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":1000000,\"completion_tokens\":1000000,\"total_tokens\":2000000}}\n\n",
+        "data: [DONE]\n\n",
+    );
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(json!({"stream": true, "stream_options": {"include_usage": true}})))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .mount(&server)
+        .await;
+    
+    let budget = Arc::new(Budget::unlimited());
+    let mut llm = local_llm(&server)?;
+    llm.set_price("gpt-4o-mini", ModelPrice::new(1.0, 2.0));
+    llm.set_budget(budget.clone());
+    
+    // The usage chunk is charged once the stream ends
+    let mut stream = llm.stream_response("Hello").await?;
+    assert_eq!(stream.next().await.transpose()?, Some("Hi".to_string()));
+    assert_eq!(llm.total_cost(), 0.0);
+    assert!(stream.next().await.is_none());
+    assert_eq!(stream.usage().map(|usage| usage.total_tokens), Some(2_000_000));
+    assert_eq!(llm.total_cost(), 3.0);
+    assert_eq!(budget.calls(), 1);
+    
+    // Providers that report no usage are charged by counting tokens
+    let mut mock = LLMInterface::with_provider(
+        LLMConfig::new("gpt-4o-mini".to_string()),
+        MockProvider::new().reply("Bonjour tout le monde"),
+    );
+    mock.initialize()?;
+    mock.set_budget(budget.clone());
+    let text: String = mock.stream_response("Say hello in French").await?
+        .map(|delta| delta.unwrap())
+        .collect()
+        .await;
+    assert_eq!(text, "Bonjour tout le monde");
+    assert!(mock.total_cost() > 0.0);
+    assert_eq!(budget.calls(), 2);
+    
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use ai_playground::components::{
+    Chunker, DocumentKind, DocumentLoader, Encoding, LLMConfig, MockProvider, SourceDocument, Tokenizer,
+};
+use ai_playground::prelude::*;
+use ai_playground::Error;
+use anyhow::Result;
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("ai-playground-{}-{}", name, std::process::id()));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+#[test]
+fn test_load_dir_reads_supported_files() -> Result<()> {
+    let dir = temp_dir("docs")?;
+    std::fs::write(dir.join("guide.md"), "# Guide\n\nRead me.")?;
+    std::fs::write(dir.join("notes.txt"), "Plain notes")?;
+    std::fs::write(dir.join("page.html"), concat!(
+        "<html><head><title>Ignored</title><style>p { color: red; }</style></head><body>\n",
+        "  <h1>Welcome   <em>home</em></h1>\n",
+        "  <p>Fish &amp; chips<br>are   served.</p>\n",
+        "  <script>alert('hi')</script>\n",
+        "  <pre>fn main() {\n    println!(\"&lt;3\");\n}</pre>\n",
+        "</body></html>",
+    ))?;
+    std::fs::write(dir.join("image.png"), [0u8, 1, 2])?;
+    std::fs::write(dir.join(".hidden.md"), "secret")?;
+    std::fs::create_dir_all(dir.join("src"))?;
+    std::fs::write(dir.join("src").join("lib.rs"), "pub fn answer() -> u32 { 42 }")?;
+    std::fs::create_dir_all(dir.join("target"))?;
+    std::fs::write(dir.join("target").join("build.rs"), "fn main() {}")?;
+    std::fs::write(dir.join("huge.txt"), "x".repeat(2048))?;
+    
+    let documents = DocumentLoader::new().max_file_bytes(1024).load_dir(&dir)?;
+    let names: Vec<_> = documents.iter()
+        .map(|document| document.path.strip_prefix(&dir).unwrap().to_string_lossy().replace('\\', "/"))
+        .collect();
+    assert_eq!(names, vec!["guide.md", "notes.txt", "page.html", "src/lib.rs"]);
+    assert_eq!(documents[3].kind, DocumentKind::Code { language: "rust".to_string() });
+    
+    // HTML keeps its headings and code blocks as Markdown
+    assert_eq!(
+        documents[2].text,
+        "# Welcome home\n\nFish & chips\nare served.\n\n```\nfn main() {\n    println!(\"<3\");\n}\n```"
+    );
+    
+    let shallow = DocumentLoader::new().recursive(false).load_dir(&dir)?;
+    assert_eq!(shallow.len(), 4);
+    
+    assert!(matches!(DocumentLoader::new().load_file(dir.join("image.png")), Err(Error::Document(_))));
+    assert!(matches!(DocumentLoader::new().load_dir(dir.join("missing")), Err(Error::Document(_))));
+    
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_markdown_chunks_follow_headings_and_code_blocks() {
+    let text = concat!(
+        "# Install\n\n",
+        "Download the installer for your platform.\n\n",
+        "## Linux\n\n",
+        "Run the script below.\n\n",
+        "```sh\n",
+        "curl -sSf https://example.com/install.sh\n",
+        "\n",
+        "sh install.sh --yes\n",
+        "```\n\n",
+        "# Usage\n\n",
+        "Start the app.\n",
+    );
+    let document = SourceDocument::new("guide.md", DocumentKind::Markdown, text);
+    let chunks = Chunker::new(Tokenizer::new(Encoding::Cl100kBase), 200, 20).chunk(&document);
+    
+    let headings: Vec<_> = chunks.iter().map(|chunk| chunk.heading.as_deref()).collect();
+    assert_eq!(headings, vec![Some("Install"), Some("Install > Linux"), Some("Usage")]);
+    
+    // The code block, blank line included, stays in one chunk
+    assert!(chunks[1].text.starts_with("## Linux"));
+    assert!(chunks[1].text.ends_with("sh install.sh --yes\n```"));
+    
+    for (index, chunk) in chunks.iter().enumerate() {
+        assert_eq!(chunk.index, index);
+        assert_eq!(&text[chunk.start..chunk.end], chunk.text);
+    }
+    
+    let metadata = chunks[2].metadata();
+    assert_eq!(metadata["source"], "guide.md");
+    assert_eq!(metadata["kind"], "markdown");
+    assert_eq!(metadata["heading"], "Usage");
+    assert_eq!(metadata["start"], chunks[2].start);
+}
+
+#[test]
+fn test_long_text_is_split_with_overlap() -> Result<()> {
+    let mut llm = LLMInterface::with_provider(LLMConfig::new("gpt-4o".to_string()), MockProvider::new());
+    llm.initialize()?;
+    
+    let paragraphs: Vec<String> = (0..30)
+        .map(|i| format!("Paragraph {} talks about topic number {} in a few plain words.", i, i))
+        .collect();
+    let mut text = paragraphs.join("\n\n");
+    // A single word longer than a chunk is split by characters
+    text.push_str("\n\n");
+    text.push_str(&"abcdefghij".repeat(40));
+    let document = SourceDocument::new("notes.txt", DocumentKind::Text, text.clone());
+    
+    let chunker = Chunker::for_interface(&llm, 50, 10);
+    let chunks = chunker.chunk(&document);
+    assert!(chunks.len() > 5);
+    
+    for pair in chunks.windows(2) {
+        assert!(pair[1].start > pair[0].start);
+        assert!(pair[1].end > pair[0].end);
+    }
+    for chunk in &chunks {
+        assert!(chunk.tokens <= 50, "chunk of {} tokens", chunk.tokens);
+        assert_eq!(chunk.tokens, llm.count_tokens(&chunk.text));
+        assert_eq!(&text[chunk.start..chunk.end], chunk.text);
+    }
+    
+    // Chunks of prose share their tail with the next chunk
+    assert!(chunks[1].start < chunks[0].end);
+    let overlap = &text[chunks[1].start..chunks[0].end];
+    assert!(llm.count_tokens(overlap) <= 10);
+    
+    // Without overlap, chunks cover the prose paragraphs back to back
+    let chunks = Chunker::for_interface(&llm, 50, 0).chunk(&document);
+    assert!(chunks.windows(2).all(|pair| pair[1].start >= pair[0].end));
+    assert!(chunks[0].text.starts_with("Paragraph 0") && chunks[0].text.ends_with('.'));
+    
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use ai_playground::components::{LLMConfig, MockProvider, ProviderKind};
+use ai_playground::prelude::*;
+use ai_playground::Error;
+use anyhow::Result;
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+fn mock_llm(mock: MockProvider) -> Result<LLMInterface> {
+    let mut llm = LLMInterface::with_provider(LLMConfig::new("gpt-4o-mini".to_string()), mock);
+    llm.initialize()?;
+    Ok(llm)
+}
+
+fn texts(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+#[tokio::test]
+async fn test_inputs_are_batched_to_provider_limit() -> Result<()> {
+    let mock = MockProvider::new().embedding_batch(2);
+    let llm = mock_llm(mock.clone())?;
+    
+    let inputs = texts(&["red apples", "green apples", "blue sky", "grey sky", "fresh bread"]);
+    let vectors = llm.embed(&inputs).await?;
+    
+    assert_eq!(mock.embedding_batches(), vec![2, 2, 1]);
+    assert_eq!(vectors.len(), 5);
+    assert!(vectors.iter().all(|vector| vector.len() == 64));
+    
+    // Order survives batching, and texts sharing words are closest
+    let dot = |a: &[f32], b: &[f32]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+    assert!(dot(&vectors[0], &vectors[1]) > dot(&vectors[0], &vectors[2]));
+    assert!(dot(&vectors[2], &vectors[3]) > dot(&vectors[2], &vectors[4]));
+    
+    assert!(llm.embed(&[]).await?.is_empty());
+    assert_eq!(mock.embedding_batches().len(), 3);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_openai_embeddings_request_and_order() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/embeddings"))
+        .and(header("authorization", "Bearer test-key"))
+        .and(body_partial_json(json!({
+            "model": "text-embedding-3-large",
+            "input": ["first", "second"],
+            "dimensions": 3
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "list",
+            "data": [
+                {"object": "embedding", "index": 1, "embedding": [0.0, 1.0, 0.0]},
+                {"object": "embedding", "index": 0, "embedding": [1.0, 0.0, 0.0]}
+            ],
+            "model": "text-embedding-3-large",
+            "usage": {"prompt_tokens": 4, "total_tokens": 4}
+        })))
+        .mount(&server)
+        .await;
+    
+    let mut config = LLMConfig::new("gpt-4o-mini".to_string());
+    config.base_url = Some(server.uri());
+    config.api_key = Some("test-key".to_string());
+    let mut llm = LLMInterface::with_config(config);
+    llm.set_embedding_model("text-embedding-3-large".to_string(), Some(3));
+    llm.initialize()?;
+    
+    let vectors = llm.embed(&texts(&["first", "second"])).await?;
+    assert_eq!(vectors, vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]]);
+    assert!(llm.total_cost() > 0.0);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_gemini_and_ollama_embedding_bodies() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1beta/models/gemini-embedding-001:batchEmbedContents"))
+        .and(header("x-goog-api-key", "test-key"))
+        .and(body_partial_json(json!({
+            "requests": [{
+                "model": "models/gemini-embedding-001",
+                "content": {"parts": [{"text": "hello"}]},
+                "outputDimensionality": 2
+            }]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "embeddings": [{"values": [0.6, 0.8]}]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"models": [{"name": "llama3.2:latest"}]})))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/embed"))
+        .and(body_partial_json(json!({"model": "nomic-embed-text", "input": ["hello"]})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "model": "nomic-embed-text",
+            "embeddings": [[0.1, 0.2, 0.3]],
+            "prompt_eval_count": 1
+        })))
+        .mount(&server)
+        .await;
+    
+    let mut config = LLMConfig::new("gemini-2.0-flash".to_string());
+    config.base_url = Some(format!("{}/v1beta", server.uri()));
+    config.api_key = Some("test-key".to_string());
+    config.embedding_dimensions = Some(2);
+    let mut gemini = LLMInterface::with_config(config);
+    gemini.initialize()?;
+    assert_eq!(gemini.embed(&texts(&["hello"])).await?, vec![vec![0.6, 0.8]]);
+    
+    // Gemini reports no usage, so the tokenizer estimate is charged
+    assert!(gemini.total_cost() > 0.0);
+    
+    let mut config = LLMConfig::new("llama3.2".to_string());
+    config.provider = ProviderKind::Ollama;
+    config.base_url = Some(server.uri());
+    let mut ollama = LLMInterface::with_config(config);
+    ollama.initialize()?;
+    assert_eq!(ollama.embed(&texts(&["hello"])).await?, vec![vec![0.1, 0.2, 0.3]]);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_azure_embedding_deployment_and_mismatch() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/openai/deployments/prod-embed/embeddings"))
+        .and(header("api-key", "azure-key"))
+        .respond_with(|request: &Request| {
+            // Answer one vector short to trip the count check
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let data: Vec<_> = body["input"].as_array().unwrap().iter()
+                .skip(1)
+                .enumerate()
+                .map(|(index, _)| json!({"index": index, "embedding": [1.0]}))
+                .collect();
+            ResponseTemplate::new(200).set_body_json(json!({"data": data}))
+        })
+        .mount(&server)
+        .await;
+    
+    let mut config = LLMConfig::azure(server.uri(), "prod-chat", "gpt-4o-mini");
+    config.api_key = Some("azure-key".to_string());
+    config.azure.embedding_deployment = Some("prod-embed".to_string());
+    let mut llm = LLMInterface::with_config(config);
+    llm.initialize()?;
+    
+    let error = llm.embed(&texts(&["a", "b"])).await.unwrap_err();
+    assert!(matches!(error, Error::EmbeddingMismatch { expected: 2, actual: 1 }));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_providers_without_embeddings() -> Result<()> {
+    let mut llm = LLMInterface::with_config(LLMConfig::new("claude-3-5-haiku-latest".to_string()));
+    llm.initialize()?;
+    assert_eq!(llm.provider_name(), "Anthropic");
+    
+    let error = llm.embed(&texts(&["hello"])).await.unwrap_err();
+    assert!(matches!(error, Error::Unsupported { ref operation, .. } if operation == "embeddings"));
+    
+    Ok(())
+}
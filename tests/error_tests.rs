@@ -0,0 +1,110 @@
+use ai_playground::components::{LLMConfig, RetryPolicy};
+use ai_playground::prelude::*;
+use ai_playground::Error;
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn failing_llm(response: ResponseTemplate) -> (MockServer, LLMInterface) {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).respond_with(response).mount(&server).await;
+    
+    let mut config = LLMConfig::new("gpt-4o-mini".to_string());
+    config.base_url = Some(format!("{}/v1", server.uri()));
+    config.retry = RetryPolicy::none();
+    
+    let mut llm = LLMInterface::with_config(config);
+    llm.initialize().unwrap();
+    (server, llm)
+}
+
+#[tokio::test]
+async fn test_setup_errors_are_distinguishable() {
+    let llm = LLMInterface::new(None);
+    assert!(matches!(llm.generate_response("hi").await, Err(Error::NotInitialized)));
+    
+    let mut llm = LLMInterface::new(None);
+    llm.initialize().unwrap();
+    assert!(matches!(llm.generate_response("hi").await, Err(Error::MissingApiKey)));
+    
+    // Naming the public API explicitly still needs a key
+    llm.set_base_url("https://api.openai.com/v1".to_string());
+    assert!(matches!(llm.generate_response("hi").await, Err(Error::MissingApiKey)));
+}
+
+#[tokio::test]
+async fn test_authentication_failure() {
+    let (_server, llm) = failing_llm(ResponseTemplate::new(401).set_body_json(json!({
+        "error": {"message": "Incorrect API key provided", "code": "invalid_api_key"}
+    })))
+    .await;
+    
+    match llm.generate_response("hi").await {
+        Err(Error::Authentication { status, message }) => {
+            assert_eq!(status, 401);
+            assert_eq!(message, "Incorrect API key provided");
+        }
+        other => panic!("expected authentication error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_rate_limit_carries_retry_after() {
+    let (_server, llm) = failing_llm(
+        ResponseTemplate::new(429)
+            .insert_header("retry-after", "7")
+            .set_body_json(json!({"error": {"message": "Rate limit reached"}})),
+    )
+    .await;
+    
+    match llm.generate_response("hi").await {
+        Err(Error::RateLimited { retry_after, .. }) => {
+            assert_eq!(retry_after, Some(Duration::from_secs(7)));
+        }
+        other => panic!("expected rate limit error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_context_length_exceeded() {
+    let (_server, llm) = failing_llm(ResponseTemplate::new(400).set_body_json(json!({
+        "error": {
+            "message": "This model's maximum context length is 8192 tokens.",
+            "code": "context_length_exceeded"
+        }
+    })))
+    .await;
+    
+    assert!(matches!(
+        llm.generate_response("hi").await,
+        Err(Error::ContextLengthExceeded { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_other_statuses_are_http_errors() {
+    let (_server, llm) = failing_llm(ResponseTemplate::new(404).set_body_string("no such route")).await;
+    
+    match llm.generate_response("hi").await {
+        Err(Error::Http { status, body }) => {
+            assert_eq!(status, 404);
+            assert_eq!(body, "no such route");
+        }
+        other => panic!("expected HTTP error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_prompt_manager_errors() {
+    let mut pm = PromptManager::new();
+    assert!(matches!(pm.add_template("  ".to_string()), Err(Error::InvalidTemplate(_))));
+    assert!(matches!(pm.process(), Err(Error::NoCurrentPrompt)));
+    
+    pm.set_variable("topic".to_string(), "rust".to_string());
+    assert_eq!(pm.render_template("Explain {topic}").unwrap(), "Explain rust");
+    match pm.render_template("Explain {topic} to {audience}") {
+        Err(Error::MissingVariable(name)) => assert_eq!(name, "audience"),
+        other => panic!("expected missing variable error, got {:?}", other),
+    }
+}
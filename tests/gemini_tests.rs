@@ -0,0 +1,147 @@
+use ai_playground::components::{FinishReason, LLMConfig, Tool, ToolRegistry};
+use ai_playground::prelude::*;
+use anyhow::Result;
+use futures::StreamExt;
+use serde_json::{json, Value};
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn gemini_llm(server: &MockServer) -> Result<LLMInterface> {
+    let mut config = LLMConfig::new("gemini-2.0-flash".to_string());
+    config.base_url = Some(format!("{}/v1beta", server.uri()));
+    config.api_key = Some("test-key".to_string());
+    config.temperature = Some(0.2);
+    config.max_tokens = Some(256);
+    
+    let mut llm = LLMInterface::with_config(config);
+    llm.initialize()?;
+    Ok(llm)
+}
+
+#[tokio::test]
+async fn test_generate_content_maps_request_and_response() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1beta/models/gemini-2.0-flash:generateContent"))
+        .and(header("x-goog-api-key", "test-key"))
+        .and(body_partial_json(json!({
+            "systemInstruction": {"parts": [{"text": "Answer briefly."}]},
+            "contents": [
+                {"role": "user", "parts": [{"text": "Hi"}]},
+                {"role": "model", "parts": [{"text": "Hello!"}]},
+                {"role": "user", "parts": [{"text": "Capital of France?"}]}
+            ],
+            "generationConfig": {"temperature": 0.2, "maxOutputTokens": 256}
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "candidates": [{
+                "content": {"role": "model", "parts": [{"text": "Paris"}]},
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": {"promptTokenCount": 14, "candidatesTokenCount": 2, "totalTokenCount": 16},
+            "modelVersion": "gemini-2.0-flash-001",
+            "responseId": "abc123"
+        })))
+        .mount(&server)
+        .await;
+    
+    let llm = gemini_llm(&server).await?;
+    assert_eq!(llm.provider_name(), "Gemini");
+    
+    let mut conversation = Conversation::new();
+    conversation.add_system("Answer briefly.");
+    conversation.add_user("Hi");
+    conversation.add_assistant("Hello!");
+    conversation.add_user("Capital of France?");
+    let completion = llm.complete(&mut conversation).await?;
+    
+    assert_eq!(completion.text(), "Paris");
+    assert_eq!(completion.finish_reason, Some(FinishReason::Stop));
+    assert_eq!(completion.model.as_deref(), Some("gemini-2.0-flash-001"));
+    let usage = completion.usage.unwrap();
+    assert_eq!((usage.prompt_tokens, usage.completion_tokens, usage.total_tokens), (14, 2, 16));
+    assert!(completion.cost.unwrap() > 0.0);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_function_calls_and_structured_output() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({
+            "contents": [
+                {"role": "user"},
+                {"role": "model", "parts": [{"functionCall": {"name": "get_weather"}}]},
+                {"role": "user", "parts": [{"functionResponse": {
+                    "name": "get_weather",
+                    "response": {"content": "18C and sunny"}
+                }}]}
+            ]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "candidates": [{"content": {"parts": [{"text": "It is 18C and sunny."}]}, "finishReason": "STOP"}]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"tools": [{"functionDeclarations": [{"name": "get_weather"}]}]})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "candidates": [{
+                "content": {"parts": [{"functionCall": {"name": "get_weather", "args": {"city": "Paris"}}}]},
+                "finishReason": "STOP"
+            }]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"generationConfig": {"responseMimeType": "application/json"}})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "candidates": [{"content": {"parts": [{"text": "{\"answer\": 4}"}]}, "finishReason": "STOP"}]
+        })))
+        .mount(&server)
+        .await;
+    
+    let llm = gemini_llm(&server).await?;
+    
+    let mut tools = ToolRegistry::new();
+    tools.register(
+        Tool::new("get_weather", "Get the weather", json!({"type": "object"})),
+        |_: Value| async move { Ok("18C and sunny".to_string()) },
+    );
+    let mut conversation = Conversation::new();
+    conversation.add_user("Weather in Paris?");
+    assert_eq!(llm.chat_with_tools(&mut conversation, &tools).await?, "It is 18C and sunny.");
+    
+    let answer: Value = llm.generate_structured("What is 2 + 2?").await?;
+    assert_eq!(answer, json!({"answer": 4}));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_generate_content() -> Result<()> {
+    let server = MockServer::start().await;
+    let events = [
+        json!({"candidates": [{"content": {"role": "model", "parts": [{"text": "Bonjour"}]}}]}),
+        json!({"candidates": [{"content": {"role": "model", "parts": [{"text": " le monde"}]}, "finishReason": "STOP"}]}),
+    ];
+    let body: String = events.iter().map(|event| format!("data: {}\r\n\r\n", event)).collect();
+    Mock::given(method("POST"))
+        .and(path("/v1beta/models/gemini-2.0-flash:streamGenerateContent"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .mount(&server)
+        .await;
+    
+    let llm = gemini_llm(&server).await?;
+    let chunks: Vec<String> = llm.stream_response("Say hello in French").await?
+        .map(|chunk| chunk.unwrap())
+        .collect()
+        .await;
+    assert_eq!(chunks, vec!["Bonjour", " le monde"]);
+    
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests[0].url.query(), Some("alt=sse"));
+    
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use ai_playground::prelude::*;
+use ai_playground::Error;
 use anyhow::Result;
 
 #[tokio::test]
@@ -38,12 +39,29 @@ async fn test_prompt_manager_functionality() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_template_keys_with_punctuation() {
+    let mut pm = PromptManager::new();
+    pm.set_variable("user-name".to_string(), "Ada".to_string());
+    pm.set_variable("doc.title".to_string(), "Notes".to_string());
+    
+    let template = "Hi {user-name}, see {doc.title}";
+    assert_eq!(pm.process_template(template), "Hi Ada, see Notes");
+    assert_eq!(pm.render_template(template).unwrap(), "Hi Ada, see Notes");
+    
+    // JSON braces are not placeholders, but unknown names are
+    assert_eq!(pm.render_template(r#"Reply as {"name": "..."}"#).unwrap(), r#"Reply as {"name": "..."}"#);
+    assert!(matches!(pm.render_template("{user-role}"), Err(Error::MissingVariable(name)) if name == "user-role"));
+}
+
 #[tokio::test]
 async fn test_ai_playground_integration() -> Result<()> {
-    let mut playground = AIPlayground::new();
+    let dir = tempfile::tempdir()?;
+    let mut playground = AIPlayground::with_data_dir(dir.path());
     
     // Test initialization
     assert!(playground.initialize().is_ok());
+    assert!(dir.path().join("ai-playground-cache").is_dir());
     
     // Test component names
     assert_eq!(playground.llm_interface.name(), "LLM Interface");
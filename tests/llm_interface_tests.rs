@@ -0,0 +1,350 @@
+use ai_playground::components::{Image, LLMConfig, Message};
+use ai_playground::prelude::*;
+use anyhow::Result;
+use futures::StreamExt;
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn connected_llm(server: &MockServer) -> Result<LLMInterface> {
+    let mut llm = LLMInterface::new(Some("gpt-4o-mini".to_string()));
+    llm.initialize()?;
+    llm.set_api_key("test-key".to_string());
+    llm.set_base_url(format!("{}/v1", server.uri()));
+    Ok(llm)
+}
+
+#[tokio::test]
+async fn test_generate_response_returns_assistant_text() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(header("authorization", "Bearer test-key"))
+        .and(body_partial_json(json!({
+            "model": "gpt-4o-mini",
+            "messages": [{"role": "user", "content": "Hello"}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{"message": {"role": "assistant", "content": "Hi there!"}}]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    
+    let llm = connected_llm(&server).await?;
+    assert_eq!(llm.generate_response("Hello").await?, "Hi there!");
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_response_surfaces_api_errors() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({
+            "error": {"message": "Incorrect API key provided"}
+        })))
+        .mount(&server)
+        .await;
+    
+    let llm = connected_llm(&server).await?;
+    let err = llm.generate_response("Hello").await.unwrap_err().to_string();
+    assert!(err.contains("401"));
+    assert!(err.contains("Incorrect API key provided"));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_response_rejects_malformed_body() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("not json"))
+        .mount(&server)
+        .await;
+    
+    let llm = connected_llm(&server).await?;
+    assert!(llm.generate_response("Hello").await.is_err());
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_provider_is_inferred_from_model() -> Result<()> {
+    assert_eq!(LLMInterface::new(None).provider_name(), "OpenAI");
+    assert_eq!(LLMInterface::new(Some("claude-3-5-haiku-latest".to_string())).provider_name(), "Anthropic");
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_anthropic_provider_uses_messages_api() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(header("x-api-key", "test-key"))
+        .and(header("anthropic-version", "2023-06-01"))
+        .and(body_partial_json(json!({
+            "model": "claude-3-5-haiku-latest",
+            "max_tokens": 1000,
+            "messages": [{"role": "user", "content": "Hello"}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "content": [{"type": "text", "text": "Hello from Claude"}]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    
+    let mut llm = LLMInterface::new(Some("claude-3-5-haiku-latest".to_string()));
+    llm.initialize()?;
+    llm.set_api_key("test-key".to_string());
+    llm.set_base_url(format!("{}/v1", server.uri()));
+    
+    assert_eq!(llm.generate_response("Hello").await?, "Hello from Claude");
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_provider_switches_backend() -> Result<()> {
+    let mut llm = LLMInterface::new(Some("gpt-4o".to_string()));
+    llm.set_provider(ProviderKind::Anthropic);
+    assert_eq!(llm.provider_name(), "Anthropic");
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_base_url_headers_and_organization_are_sent() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/gateway/v1/chat/completions"))
+        .and(header("OpenAI-Organization", "org-123"))
+        .and(header("OpenAI-Project", "proj-456"))
+        .and(header("X-Team", "research"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{"message": {"role": "assistant", "content": "ok"}}]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    
+    let mut config = LLMConfig::new("gpt-4o-mini".to_string());
+    config.api_key = Some("test-key".to_string());
+    config.base_url = Some(format!("{}/gateway/v1/", server.uri()));
+    config.organization = Some("org-123".to_string());
+    config.project = Some("proj-456".to_string());
+    config.headers.insert("X-Team".to_string(), "research".to_string());
+    
+    let mut llm = LLMInterface::with_config(config);
+    llm.initialize()?;
+    assert_eq!(llm.generate_response("ping").await?, "ok");
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_local_server_does_not_require_api_key() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{"message": {"role": "assistant", "content": "local reply"}}]
+        })))
+        .mount(&server)
+        .await;
+    
+    let mut llm = LLMInterface::new(Some("llama3.2".to_string()));
+    llm.set_base_url(format!("{}/v1", server.uri()));
+    llm.initialize()?;
+    
+    assert_eq!(llm.generate_response("Hello").await?, "local reply");
+    assert!(server.received_requests().await.unwrap()[0].headers.get("authorization").is_none());
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_response_yields_openai_deltas() -> Result<()> {
+    let server = MockServer::start().await;
+    let body = concat!(
+        "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+        ": keep-alive\n\n",
+        "event: ping\n\n",
+        "data: \n\n",
+        "data: {\"choices\":[{\"delta\":{\"content\":\"lo!\"}}]}\n\n",
+        "data: [DONE]\n\n",
+    );
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(json!({"stream": true})))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    
+    let llm = connected_llm(&server).await?;
+    let deltas: Vec<String> = llm.stream_response("Hello").await?
+        .map(|delta| delta.unwrap())
+        .collect()
+        .await;
+    assert_eq!(deltas, vec!["Hel", "lo!"]);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_response_yields_anthropic_deltas() -> Result<()> {
+    let server = MockServer::start().await;
+    let body = concat!(
+        "event: message_start\ndata: {\"type\":\"message_start\",\"message\":{}}\n\n",
+        "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hi \"}}\n\n",
+        "event: ping\ndata: {\"type\":\"ping\"}\n\n",
+        "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"there\"}}\n\n",
+        "event: message_stop\ndata: {\"type\":\"message_stop\"}\n\n",
+    );
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .mount(&server)
+        .await;
+    
+    let mut llm = LLMInterface::new(Some("claude-3-5-haiku-latest".to_string()));
+    llm.initialize()?;
+    llm.set_api_key("test-key".to_string());
+    llm.set_base_url(format!("{}/v1", server.uri()));
+    
+    let deltas: Vec<String> = llm.stream_response("Hello").await?
+        .map(|delta| delta.unwrap())
+        .collect()
+        .await;
+    assert_eq!(deltas, vec!["Hi ", "there"]);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_response_surfaces_http_errors() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("upstream failure"))
+        .mount(&server)
+        .await;
+    
+    let llm = connected_llm(&server).await?;
+    let err = llm.stream_response("Hello").await.err().unwrap().to_string();
+    assert!(err.contains("500"));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_chat_sends_history_and_appends_reply() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "You are terse."},
+                {"role": "user", "content": "Hi"},
+                {"role": "assistant", "content": "Hello."},
+                {"role": "user", "content": "How are you?"}
+            ]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{"message": {"role": "assistant", "content": "Fine."}}]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    
+    let llm = connected_llm(&server).await?;
+    let mut conversation = Conversation::with_system("You are terse.");
+    conversation.add_user("Hi");
+    conversation.add_assistant("Hello.");
+    conversation.add_user("How are you?");
+    
+    assert_eq!(llm.chat(&mut conversation).await?, "Fine.");
+    assert_eq!(conversation.len(), 5);
+    assert_eq!(conversation.last_reply(), Some("Fine."));
+    
+    conversation.clear();
+    assert_eq!(conversation.len(), 1);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_anthropic_chat_moves_system_prompt_to_top_level() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(body_partial_json(json!({
+            "system": "You are terse.",
+            "messages": [{"role": "user", "content": "Hi"}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "content": [{"type": "text", "text": "Hello."}]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    
+    let mut llm = LLMInterface::new(Some("claude-3-5-haiku-latest".to_string()));
+    llm.initialize()?;
+    llm.set_base_url(format!("{}/v1", server.uri()));
+    
+    let mut conversation = Conversation::with_system("You are terse.");
+    conversation.add_user("Hi");
+    assert_eq!(llm.chat(&mut conversation).await?, "Hello.");
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_images_are_sent_in_each_provider_format() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(json!({
+            "messages": [{"role": "user", "content": [
+                {"type": "text", "text": "Describe this"},
+                {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}}
+            ]}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{"message": {"role": "assistant", "content": "A cat"}}]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(body_partial_json(json!({
+            "messages": [{"role": "user", "content": [
+                {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "iVBORw0KGgo="}},
+                {"type": "text", "text": "Describe this"}
+            ]}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "content": [{"type": "text", "text": "A tiny image"}]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    
+    let llm = connected_llm(&server).await?;
+    let mut conversation = Conversation::new();
+    conversation.push(Message::user("Describe this").with_image(Image::url("https://example.com/cat.png")));
+    assert_eq!(llm.chat(&mut conversation).await?, "A cat");
+    
+    let mut llm = LLMInterface::new(Some("claude-sonnet-4".to_string()));
+    llm.initialize()?;
+    llm.set_base_url(format!("{}/v1", server.uri()));
+    let mut conversation = Conversation::new();
+    conversation.push(Message::user("Describe this").with_image(Image::base64("image/png", "iVBORw0KGgo=")));
+    assert_eq!(llm.chat(&mut conversation).await?, "A tiny image");
+    
+    Ok(())
+}
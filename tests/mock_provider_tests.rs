@@ -0,0 +1,117 @@
+use ai_playground::components::{
+    LLMConfig, MockError, MockProvider, MockResponse, Tool, ToolRegistry,
+};
+use ai_playground::prelude::*;
+use ai_playground::Error;
+use anyhow::Result;
+use futures::StreamExt;
+use serde_json::json;
+use std::time::{Duration, Instant};
+
+fn mock_llm(mock: &MockProvider) -> Result<LLMInterface> {
+    let mut llm = LLMInterface::with_provider(LLMConfig::new("mock-model".to_string()), mock.clone());
+    llm.initialize()?;
+    Ok(llm)
+}
+
+#[tokio::test]
+async fn test_canned_replies_and_recorded_requests() -> Result<()> {
+    let mock = MockProvider::new().reply("First").reply("Second");
+    let llm = mock_llm(&mock)?;
+    
+    assert_eq!(llm.provider_name(), "Mock");
+    assert_eq!(llm.generate_response("One").await?, "First");
+    let completion = llm.generate("Two").await?;
+    assert_eq!(completion.text(), "Second");
+    assert!(completion.usage.unwrap().prompt_tokens > 0);
+    
+    // Nothing left in the script
+    assert!(matches!(llm.generate("Three").await, Err(Error::Mock(_))));
+    
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].model, "mock-model");
+    assert_eq!(requests[1].last_user_message(), Some("Two"));
+    assert!(!requests[1].stream);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rules_take_precedence_over_queue() -> Result<()> {
+    let mock = MockProvider::new()
+        .when(r"(?i)weather in \w+", "Sunny")
+        .reply("Queued")
+        .default_reply("Fallback");
+    let llm = mock_llm(&mock)?;
+    
+    assert_eq!(llm.generate_response("What's the weather in Paris?").await?, "Sunny");
+    assert_eq!(llm.generate_response("Hello").await?, "Queued");
+    assert_eq!(llm.generate_response("Hello again").await?, "Fallback");
+    assert_eq!(llm.generate_response("WEATHER IN Oslo").await?, "Sunny");
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_injected_errors_and_latency() -> Result<()> {
+    let mock = MockProvider::new()
+        .fail(MockError::RateLimited { retry_after: Some(Duration::from_secs(2)) })
+        .fail(MockError::ServerError)
+        .fail(MockError::Timeout)
+        .latency(Duration::from_millis(20));
+    let llm = mock_llm(&mock)?;
+    
+    let start = Instant::now();
+    assert!(matches!(
+        llm.generate("a").await,
+        Err(Error::RateLimited { retry_after: Some(delay), .. }) if delay == Duration::from_secs(2)
+    ));
+    assert!(start.elapsed() >= Duration::from_millis(20));
+    assert!(matches!(llm.generate("b").await, Err(Error::Http { status: 500, .. })));
+    assert!(matches!(llm.generate("c").await, Err(Error::Timeout)));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_simulated_streaming() -> Result<()> {
+    let mock = MockProvider::new()
+        .reply("Streaming works fine")
+        .chunk_delay(Duration::from_millis(1));
+    let llm = mock_llm(&mock)?;
+    
+    let chunks: Vec<String> = llm.stream_response("Stream please").await?
+        .map(|chunk| chunk.unwrap())
+        .collect()
+        .await;
+    
+    assert_eq!(chunks, vec!["Streaming ", "works ", "fine"]);
+    assert!(mock.last_request().unwrap().stream);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_scripted_tool_calls() -> Result<()> {
+    let mock = MockProvider::new()
+        .respond(MockResponse::tool_call("add", json!({"a": 2, "b": 3})))
+        .reply("The sum is 5");
+    let llm = mock_llm(&mock)?;
+    
+    let mut tools = ToolRegistry::new();
+    tools.register(
+        Tool::new("add", "Add two numbers", json!({"type": "object"})),
+        |args| async move { Ok((args["a"].as_i64().unwrap() + args["b"].as_i64().unwrap()).to_string()) },
+    );
+    
+    let mut conversation = Conversation::new();
+    conversation.add_user("What is 2 + 3?");
+    assert_eq!(llm.chat_with_tools(&mut conversation, &tools).await?, "The sum is 5");
+    
+    let second = &mock.requests()[1];
+    assert_eq!(second.tools.len(), 1);
+    assert_eq!(second.messages.last().unwrap().content, "5");
+    
+    Ok(())
+}
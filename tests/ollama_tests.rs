@@ -0,0 +1,200 @@
+use ai_playground::components::{
+    LLMConfig, OllamaOptions, OllamaProvider, ProviderKind, RetryPolicy, Tool, ToolRegistry,
+};
+use ai_playground::prelude::*;
+use ai_playground::Error;
+use anyhow::Result;
+use futures::StreamExt;
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn ollama_config(server: &MockServer, model: &str) -> LLMConfig {
+    let mut config = LLMConfig::new(model.to_string());
+    config.provider = ProviderKind::Ollama;
+    config.base_url = Some(server.uri());
+    config
+}
+
+async fn mount_tags(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/api/tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "models": [
+                {"name": "llama3.2:latest", "size": 2019393189},
+                {"name": "qwen2.5:0.5b", "size": 397821319}
+            ]
+        })))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_initialize_checks_model_is_pulled() -> Result<()> {
+    let server = MockServer::start().await;
+    mount_tags(&server).await;
+    
+    // An untagged name matches the `latest` tag
+    let mut llm = LLMInterface::with_config(ollama_config(&server, "llama3.2"));
+    llm.initialize_async().await?;
+    assert_eq!(llm.provider_name(), "Ollama");
+    assert_eq!(llm.list_models().await?, vec!["llama3.2:latest", "qwen2.5:0.5b"]);
+    
+    let mut missing = LLMInterface::with_config(ollama_config(&server, "mistral"));
+    assert!(matches!(missing.initialize_async().await, Err(Error::ModelNotPulled(model)) if model == "mistral"));
+    assert!(!missing.is_connected());
+    
+    // Nothing is listening on the discard port
+    let mut config = LLMConfig::new("llama3.2".to_string());
+    config.provider = ProviderKind::Ollama;
+    config.base_url = Some("http://127.0.0.1:9".to_string());
+    config.retry = RetryPolicy { max_attempts: 1, ..RetryPolicy::default() };
+    let mut unreachable = LLMInterface::with_config(config);
+    assert!(unreachable.initialize_async().await.is_err());
+    assert!(!unreachable.is_connected());
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_api_key_is_sent_to_every_endpoint() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/tags"))
+        .and(header("authorization", "Bearer proxy-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "models": [{"name": "llama3.2:latest"}]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/embed"))
+        .and(header("authorization", "Bearer proxy-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "embeddings": [[0.1, 0.2]]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .and(header("authorization", "Bearer proxy-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "message": {"role": "assistant", "content": "Hi"},
+            "done": true
+        })))
+        .mount(&server)
+        .await;
+    
+    let mut config = ollama_config(&server, "llama3.2");
+    config.api_key = Some("proxy-key".to_string());
+    let mut llm = LLMInterface::with_config(config);
+    llm.initialize_async().await?;
+    assert_eq!(llm.list_models().await?, vec!["llama3.2:latest"]);
+    assert_eq!(llm.embed(&["hello".to_string()]).await?, vec![vec![0.1, 0.2]]);
+    assert_eq!(llm.generate_response("Hello").await?, "Hi");
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_chat_sends_options_and_parses_tool_calls() -> Result<()> {
+    let server = MockServer::start().await;
+    mount_tags(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .and(body_partial_json(json!({
+            "model": "qwen2.5:0.5b",
+            "stream": false,
+            "options": {"temperature": 0.0, "num_predict": 200, "num_ctx": 8192, "seed": 42, "top_k": 20}
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "model": "qwen2.5:0.5b",
+            "message": {
+                "role": "assistant",
+                "content": "",
+                "tool_calls": [{"function": {"name": "add", "arguments": {"a": 2, "b": 3}}}]
+            },
+            "done": true,
+            "done_reason": "stop",
+            "prompt_eval_count": 31,
+            "eval_count": 12
+        })))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .and(body_partial_json(json!({
+            "messages": [{"role": "user"}, {"role": "assistant"}, {"role": "tool", "content": "5"}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "message": {"role": "assistant", "content": "2 + 3 = 5"},
+            "done": true,
+            "done_reason": "stop"
+        })))
+        .mount(&server)
+        .await;
+    
+    let mut config = ollama_config(&server, "qwen2.5:0.5b");
+    config.temperature = Some(0.0);
+    config.max_tokens = Some(200);
+    config.ollama = OllamaOptions {
+        num_ctx: Some(8192),
+        seed: Some(42),
+        top_k: Some(20),
+    };
+    let mut llm = LLMInterface::with_config(config);
+    llm.initialize()?;
+    
+    let mut tools = ToolRegistry::new();
+    tools.register(
+        Tool::new("add", "Add two numbers", json!({"type": "object"})),
+        |args: serde_json::Value| async move {
+            Ok((args["a"].as_i64().unwrap_or(0) + args["b"].as_i64().unwrap_or(0)).to_string())
+        },
+    );
+    let mut conversation = Conversation::new();
+    conversation.add_user("What is 2 + 3?");
+    assert_eq!(llm.chat_with_tools(&mut conversation, &tools).await?, "2 + 3 = 5");
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ndjson_streaming_and_generate_endpoint() -> Result<()> {
+    let server = MockServer::start().await;
+    mount_tags(&server).await;
+    let lines = [
+        json!({"response": "Hello", "done": false}),
+        json!({"response": ", wor", "done": false}),
+        json!({"response": "ld", "done": false}),
+        json!({"response": "", "done": true, "done_reason": "stop", "eval_count": 3}),
+    ];
+    let body: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+    Mock::given(method("POST"))
+        .and(path("/api/generate"))
+        .and(body_partial_json(json!({"prompt": "Say hello", "stream": true})))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/x-ndjson"))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/generate"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({"error": "model not found"})))
+        .mount(&server)
+        .await;
+    
+    let mut llm = LLMInterface::with_provider(ollama_config(&server, "llama3.2"), OllamaProvider::generate());
+    llm.initialize()?;
+    
+    let chunks: Vec<String> = llm.stream_response("Say hello").await?
+        .map(|chunk| chunk.unwrap())
+        .collect()
+        .await;
+    assert_eq!(chunks, vec!["Hello", ", wor", "ld"]);
+    
+    // Plain-string error bodies keep their message
+    let error = llm.generate_response("Anything else").await.unwrap_err();
+    assert!(matches!(error, Error::Http { status: 404, ref body } if body == "model not found"));
+    
+    Ok(())
+}
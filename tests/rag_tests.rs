@@ -0,0 +1,115 @@
+use ai_playground::components::{
+    Chunker, DocumentKind, LLMConfig, MetadataFilter, MockProvider, RagPipeline, SourceDocument,
+    VectorStore,
+};
+use ai_playground::prelude::*;
+use ai_playground::Error;
+use anyhow::Result;
+
+const GUIDE: &str = concat!(
+    "# Ferris\n\n",
+    "Ferris is the unofficial mascot of the Rust programming language. Ferris is a crab.\n\n",
+    "# Cargo\n\n",
+    "Cargo is the Rust package manager. It builds crates and downloads dependencies.\n\n",
+    "# Weather\n\n",
+    "It rains a lot in the Pacific Northwest in winter.\n",
+);
+
+fn mock_llm(mock: MockProvider) -> Result<LLMInterface> {
+    let mut llm = LLMInterface::with_provider(LLMConfig::new("gpt-4o-mini".to_string()), mock);
+    llm.initialize()?;
+    Ok(llm)
+}
+
+async fn indexed_pipeline(llm: &LLMInterface) -> Result<RagPipeline> {
+    let document = SourceDocument::new("guide.md", DocumentKind::Markdown, GUIDE);
+    let chunks = Chunker::for_interface(llm, 100, 0).chunk(&document);
+    assert_eq!(chunks.len(), 3);
+    
+    let mut rag = RagPipeline::new(VectorStore::new()).with_top_k(2);
+    assert_eq!(rag.index(llm, &chunks).await?, 3);
+    Ok(rag)
+}
+
+#[tokio::test]
+async fn test_answer_returns_cited_chunks() -> Result<()> {
+    let mock = MockProvider::new().reply("Ferris is a crab [1], the Rust mascot [1, 7].");
+    let llm = mock_llm(mock.clone())?;
+    let rag = indexed_pipeline(&llm).await?;
+    
+    let answer = rag.ask(&llm, "Who is Ferris, the Rust crab?").await?;
+    assert_eq!(answer.answer, "Ferris is a crab [1], the Rust mascot [1, 7].");
+    assert_eq!(answer.retrieved.len(), 2);
+    assert!(answer.retrieved[0].document.text.starts_with("# Ferris"));
+    
+    // Repeated and out-of-range citations are dropped
+    assert_eq!(answer.cited.len(), 1);
+    assert_eq!(answer.cited[0], answer.retrieved[0]);
+    assert_eq!(answer.cited[0].document.metadata["heading"], "Ferris");
+    
+    // The prompt numbers the retrieved chunks and labels their source
+    let prompt = mock.last_request().unwrap().last_user_message().unwrap().to_string();
+    assert!(prompt.contains("Cite every source you use"));
+    assert!(prompt.contains("[1] (guide.md > Ferris)\n# Ferris"));
+    assert!(prompt.contains("[2] (guide.md > "));
+    assert!(prompt.ends_with("Question: Who is Ferris, the Rust crab?"));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_custom_template_and_filter() -> Result<()> {
+    let mock = MockProvider::new().reply("Cargo builds crates [1].");
+    let llm = mock_llm(mock.clone())?;
+    let rag = indexed_pipeline(&llm).await?
+        .with_template("Q: {question}\n\n{sources}\n\nCite as [n].")?
+        .with_filter(MetadataFilter::equals("heading", "Cargo"));
+    
+    let answer = rag.ask(&llm, "Who is Ferris?").await?;
+    assert_eq!(answer.retrieved.len(), 1);
+    assert!(answer.cited[0].document.text.contains("package manager"));
+    
+    let prompt = mock.last_request().unwrap().last_user_message().unwrap().to_string();
+    assert!(prompt.starts_with("Q: Who is Ferris?\n\n[1] (guide.md > Cargo)"));
+    
+    let missing = RagPipeline::new(VectorStore::new()).with_template("Answer {question}");
+    assert!(matches!(missing, Err(Error::InvalidTemplate(_))));
+    let unfilled = RagPipeline::new(VectorStore::new()).with_template("{sources} {question} in {language}");
+    assert!(matches!(unfilled, Err(Error::MissingVariable(name)) if name == "language"));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_placeholders_in_chunks_are_not_substituted() -> Result<()> {
+    let document = SourceDocument::new(
+        "template.md",
+        DocumentKind::Markdown,
+        "Write {question} where the user's question goes and {sources} for the excerpts.",
+    );
+    let llm = mock_llm(MockProvider::new())?;
+    let chunks = Chunker::for_interface(&llm, 100, 0).chunk(&document);
+    let mut rag = RagPipeline::new(VectorStore::new());
+    rag.index(&llm, &chunks).await?;
+    
+    let retrieved = rag.retrieve(&llm, "How do I {sources}?").await?;
+    let prompt = rag.render_prompt("How do I {sources}?", &retrieved)?;
+    assert!(prompt.contains("[1] (template.md)\nWrite {question} where the user's question goes and {sources} for"));
+    assert!(prompt.ends_with("Question: How do I {sources}?"));
+    assert_eq!(prompt.matches("{question}").count(), 1);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_empty_store_does_not_call_the_model() -> Result<()> {
+    let mock = MockProvider::new();
+    let llm = mock_llm(mock.clone())?;
+    let rag = RagPipeline::new(VectorStore::new());
+    
+    let answer = rag.ask(&llm, "Anything?").await?;
+    assert!(answer.retrieved.is_empty() && answer.cited.is_empty());
+    assert_eq!(mock.request_count(), 0);
+    
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use ai_playground::components::{LLMConfig, RateLimiter};
+use ai_playground::prelude::*;
+use anyhow::Result;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_acquire_waits_for_token_refill() -> Result<()> {
+    // 60k tokens per minute refills at 1000 tokens per second
+    let limiter = RateLimiter::new(None, Some(60_000));
+    limiter.acquire(60_000).await;
+    
+    let start = Instant::now();
+    limiter.acquire(300).await;
+    assert!(start.elapsed() >= Duration::from_millis(250));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_headers_lower_available_quota() -> Result<()> {
+    let limiter = RateLimiter::new(Some(500), Some(100_000));
+    assert_eq!(limiter.available_requests(), Some(500));
+    
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("x-ratelimit-remaining-requests", "3".parse()?);
+    headers.insert("anthropic-ratelimit-tokens-remaining", "1200".parse()?);
+    limiter.update_from_headers(&headers);
+    
+    assert_eq!(limiter.available_requests(), Some(3));
+    assert!(limiter.available_tokens().unwrap() < 1_300);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_limiter_is_shared_between_interfaces() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("x-ratelimit-remaining-requests", "0")
+                .set_body_json(json!({
+                    "choices": [{"message": {"role": "assistant", "content": "ok"}}]
+                })),
+        )
+        .mount(&server)
+        .await;
+    
+    // 1200 requests per minute refills one request every 50ms
+    let limiter = Arc::new(RateLimiter::new(Some(1_200), None));
+    let mut interfaces = Vec::new();
+    for _ in 0..2 {
+        let mut config = LLMConfig::new("gpt-4o-mini".to_string());
+        config.base_url = Some(format!("{}/v1", server.uri()));
+        let mut llm = LLMInterface::with_config(config);
+        llm.initialize()?;
+        llm.set_rate_limiter(limiter.clone());
+        interfaces.push(llm);
+    }
+    
+    interfaces[0].generate_response("first").await?;
+    let start = Instant::now();
+    interfaces[1].generate_response("second").await?;
+    assert!(start.elapsed() >= Duration::from_millis(40));
+    
+    Ok(())
+}
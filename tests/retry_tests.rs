@@ -0,0 +1,126 @@
+use ai_playground::components::{LLMConfig, RetryPolicy};
+use ai_playground::prelude::*;
+use anyhow::Result;
+use serde_json::json;
+use std::time::{Duration, Instant};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn ok_reply() -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "choices": [{"message": {"role": "assistant", "content": "ok"}}]
+    }))
+}
+
+async fn fast_retry_llm(server: &MockServer) -> Result<LLMInterface> {
+    let mut config = LLMConfig::new("gpt-4o-mini".to_string());
+    config.base_url = Some(format!("{}/v1", server.uri()));
+    config.retry = RetryPolicy {
+        max_attempts: 3,
+        base_delay_ms: 1,
+        max_delay_ms: 500,
+        jitter: 0.0,
+    };
+    
+    let mut llm = LLMInterface::with_config(config);
+    llm.initialize()?;
+    Ok(llm)
+}
+
+#[tokio::test]
+async fn test_server_errors_are_retried() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(529))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST")).respond_with(ok_reply()).mount(&server).await;
+    
+    let llm = fast_retry_llm(&server).await?;
+    assert_eq!(llm.generate_response("Hello").await?, "ok");
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rate_limit_waits_for_retry_after() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after-ms", "200"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST")).respond_with(ok_reply()).mount(&server).await;
+    
+    let llm = fast_retry_llm(&server).await?;
+    let start = Instant::now();
+    assert_eq!(llm.generate_response("Hello").await?, "ok");
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_client_errors_are_not_retried() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "error": {"message": "Invalid request"}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    
+    let llm = fast_retry_llm(&server).await?;
+    assert!(llm.generate_response("Hello").await.is_err());
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retries_stop_at_max_attempts() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(3)
+        .mount(&server)
+        .await;
+    
+    let llm = fast_retry_llm(&server).await?;
+    let err = llm.generate_response("Hello").await.unwrap_err();
+    assert!(err.to_string().contains("500"));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_backoff_delay_is_exponential_and_capped() -> Result<()> {
+    let policy = RetryPolicy {
+        max_attempts: 10,
+        base_delay_ms: 100,
+        max_delay_ms: 1_000,
+        jitter: 0.0,
+    };
+    
+    assert_eq!(policy.delay(1, None), Duration::from_millis(100));
+    assert_eq!(policy.delay(3, None), Duration::from_millis(400));
+    assert_eq!(policy.delay(8, None), Duration::from_millis(1_000));
+    
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("x-ratelimit-remaining-tokens", "0".parse()?);
+    headers.insert("x-ratelimit-reset-tokens", "1m30s".parse()?);
+    let patient = RetryPolicy { max_delay_ms: 120_000, ..policy.clone() };
+    assert_eq!(patient.delay(1, Some(&headers)), Duration::from_secs(90));
+    
+    // Server hints are capped too
+    assert_eq!(policy.delay(1, Some(&headers)), Duration::from_millis(1_000));
+    
+    Ok(())
+}
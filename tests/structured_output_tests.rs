@@ -0,0 +1,119 @@
+use ai_playground::prelude::*;
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[derive(Debug, PartialEq, Deserialize, JsonSchema)]
+struct Person {
+    name: String,
+    age: u32,
+}
+
+fn openai_reply(content: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "choices": [{"message": {"role": "assistant", "content": content}}]
+    }))
+}
+
+async fn local_llm(server: &MockServer, model: &str) -> Result<LLMInterface> {
+    let mut llm = LLMInterface::new(Some(model.to_string()));
+    llm.initialize()?;
+    llm.set_base_url(format!("{}/v1", server.uri()));
+    Ok(llm)
+}
+
+#[tokio::test]
+async fn test_generate_structured_sends_json_schema_and_decodes() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(json!({
+            "response_format": {
+                "type": "json_schema",
+                "json_schema": {"name": "Person", "schema": {"required": ["name", "age"]}}
+            }
+        })))
+        .respond_with(openai_reply(r#"{"name": "Ada", "age": 36}"#))
+        .expect(1)
+        .mount(&server)
+        .await;
+    
+    let llm = local_llm(&server, "gpt-4o-mini").await?;
+    let person: Person = llm.generate_structured("Extract: Ada Lovelace, 36").await?;
+    assert_eq!(person, Person { name: "Ada".to_string(), age: 36 });
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_structured_retries_with_validation_error() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(openai_reply(r#"{"name": "Ada", "age": "thirty-six"}"#))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(openai_reply("```json\n{\"name\": \"Ada\", \"age\": 36}\n```"))
+        .mount(&server)
+        .await;
+    
+    let llm = local_llm(&server, "gpt-4o-mini").await?;
+    let person: Person = llm.generate_structured("Extract: Ada Lovelace, 36").await?;
+    assert_eq!(person.age, 36);
+    
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    let retry: Value = serde_json::from_slice(&requests[1].body)?;
+    let correction = retry["messages"][2]["content"].as_str().unwrap();
+    assert!(correction.contains("did not match the required JSON schema"));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_structured_gives_up_after_repeated_failures() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(openai_reply("I cannot do that."))
+        .expect(3)
+        .mount(&server)
+        .await;
+    
+    let llm = local_llm(&server, "gpt-4o-mini").await?;
+    let err = llm.generate_structured::<Person>("Extract nothing").await.unwrap_err();
+    assert!(err.to_string().contains("after 3 attempts"));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_anthropic_structured_output_forces_tool_call() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(body_partial_json(json!({
+            "tool_choice": {"type": "tool", "name": "Person"},
+            "tools": [{"name": "Person"}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "content": [{
+                "type": "tool_use",
+                "id": "toolu_1",
+                "name": "Person",
+                "input": {"name": "Grace", "age": 85}
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    
+    let llm = local_llm(&server, "claude-3-5-haiku-latest").await?;
+    let person: Person = llm.generate_structured("Extract: Grace Hopper, 85").await?;
+    assert_eq!(person, Person { name: "Grace".to_string(), age: 85 });
+    
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use ai_playground::components::{Encoding, Message, Tokenizer};
+use ai_playground::prelude::*;
+
+#[test]
+fn test_model_selects_encoding() {
+    assert_eq!(Tokenizer::for_model("gpt-4o-mini").encoding(), Encoding::O200kBase);
+    assert_eq!(Tokenizer::for_model("o3-mini").encoding(), Encoding::O200kBase);
+    assert_eq!(Tokenizer::for_model("gpt-4").encoding(), Encoding::Cl100kBase);
+    assert_eq!(Tokenizer::for_model("gpt-3.5-turbo").encoding(), Encoding::Cl100kBase);
+    
+    assert!(Tokenizer::for_model("gpt-4o").is_exact());
+    assert!(!Tokenizer::for_model("claude-3-5-sonnet-latest").is_exact());
+}
+
+#[test]
+fn test_count_tokens() {
+    let cl100k = Tokenizer::new(Encoding::Cl100kBase);
+    assert_eq!(cl100k.count_tokens("Hello, world!"), 4);
+    assert_eq!(cl100k.count_tokens(""), 0);
+    
+    // The Anthropic approximation errs on the high side
+    let claude = Tokenizer::for_model("claude-3-5-haiku-latest");
+    let text = "The quick brown fox jumps over the lazy dog.";
+    assert!(claude.count_tokens(text) > cl100k.count_tokens(text));
+}
+
+#[test]
+fn test_count_conversation_tokens() {
+    let llm = LLMInterface::new(Some("gpt-4".to_string()));
+    let mut conversation = Conversation::with_system("Be brief.");
+    conversation.add_user("Hello, world!");
+    
+    // Three tokens of framing per message plus three to prime the reply
+    let content = llm.count_tokens("Be brief.") + llm.count_tokens("Hello, world!");
+    assert_eq!(llm.count_conversation_tokens(&conversation), content + 3 * 2 + 3);
+    
+    let tokenizer = llm.tokenizer();
+    assert_eq!(
+        tokenizer.count_conversation_tokens(&[Message::user("Hello, world!")]),
+        tokenizer.count_message_tokens(&Message::user("Hello, world!")) + 3
+    );
+}
@@ -0,0 +1,148 @@
+use ai_playground::components::{Tool, ToolCall, ToolRegistry};
+use ai_playground::prelude::*;
+use anyhow::Result;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn weather_tools() -> ToolRegistry {
+    let mut tools = ToolRegistry::new();
+    tools.register(
+        Tool::new(
+            "get_weather",
+            "Get the current weather for a city",
+            json!({
+                "type": "object",
+                "properties": {"city": {"type": "string"}},
+                "required": ["city"]
+            }),
+        ),
+        |arguments: Value| async move {
+            let city = arguments["city"].as_str().unwrap_or("unknown").to_string();
+            Ok(format!("Sunny and 22C in {}", city))
+        },
+    );
+    tools
+}
+
+async fn request_body(server: &MockServer, index: usize) -> Value {
+    let requests = server.received_requests().await.unwrap();
+    serde_json::from_slice(&requests[index].body).unwrap()
+}
+
+#[tokio::test]
+async fn test_openai_tool_calls_are_dispatched_until_final_answer() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{"message": {
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {"name": "get_weather", "arguments": "{\"city\":\"Paris\"}"}
+                }]
+            }}]
+        })))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{"message": {"role": "assistant", "content": "It is sunny in Paris."}}]
+        })))
+        .mount(&server)
+        .await;
+    
+    let mut llm = LLMInterface::new(Some("gpt-4o-mini".to_string()));
+    llm.initialize()?;
+    llm.set_base_url(format!("{}/v1", server.uri()));
+    
+    let mut conversation = Conversation::new();
+    conversation.add_user("What's the weather in Paris?");
+    let answer = llm.chat_with_tools(&mut conversation, &weather_tools()).await?;
+    assert_eq!(answer, "It is sunny in Paris.");
+    assert_eq!(conversation.len(), 4);
+    
+    let first = request_body(&server, 0).await;
+    assert_eq!(first["tools"][0]["type"], "function");
+    assert_eq!(first["tools"][0]["function"]["name"], "get_weather");
+    
+    let second = request_body(&server, 1).await;
+    assert_eq!(second["messages"][1]["tool_calls"][0]["function"]["arguments"], "{\"city\":\"Paris\"}");
+    assert_eq!(second["messages"][2], json!({
+        "role": "tool",
+        "content": "Sunny and 22C in Paris",
+        "tool_call_id": "call_1"
+    }));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_anthropic_tool_use_blocks_round_trip() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "content": [
+                {"type": "text", "text": "Let me check."},
+                {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {"city": "Oslo"}}
+            ]
+        })))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "content": [{"type": "text", "text": "Oslo is sunny."}]
+        })))
+        .mount(&server)
+        .await;
+    
+    let mut llm = LLMInterface::new(Some("claude-3-5-haiku-latest".to_string()));
+    llm.initialize()?;
+    llm.set_base_url(format!("{}/v1", server.uri()));
+    
+    let mut conversation = Conversation::new();
+    conversation.add_user("Weather in Oslo?");
+    let answer = llm.chat_with_tools(&mut conversation, &weather_tools()).await?;
+    assert_eq!(answer, "Oslo is sunny.");
+    
+    let first = request_body(&server, 0).await;
+    assert_eq!(first["tools"][0]["input_schema"]["required"], json!(["city"]));
+    
+    let second = request_body(&server, 1).await;
+    assert_eq!(second["messages"][1], json!({
+        "role": "assistant",
+        "content": [
+            {"type": "text", "text": "Let me check."},
+            {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {"city": "Oslo"}}
+        ]
+    }));
+    assert_eq!(second["messages"][2], json!({
+        "role": "user",
+        "content": [{"type": "tool_result", "tool_use_id": "toolu_1", "content": "Sunny and 22C in Oslo"}]
+    }));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_registry_rejects_unknown_tool() -> Result<()> {
+    let tools = weather_tools();
+    let call = ToolCall {
+        id: "call_9".to_string(),
+        name: "launch_rocket".to_string(),
+        arguments: json!({}),
+    };
+    
+    let err = tools.call(&call).await.unwrap_err().to_string();
+    assert!(err.contains("Unknown tool"));
+    
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use ai_playground::components::{LLMConfig, ModelCatalog, TruncationStrategy};
+use ai_playground::prelude::*;
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A model whose context window leaves 100 prompt tokens after the reply
+const TINY_CATALOG: &str = r#"
+[models."tiny-model"]
+provider = "openai"
+context_window = 150
+max_output_tokens = 50
+features = ["tools", "json", "streaming"]
+"#;
+
+fn reply(content: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "choices": [{"message": {"role": "assistant", "content": content}, "finish_reason": "stop"}]
+    }))
+}
+
+async fn tiny_llm(server: &MockServer, strategy: TruncationStrategy) -> Result<LLMInterface> {
+    let mut config = LLMConfig::new("tiny-model".to_string());
+    config.max_tokens = Some(50);
+    config.base_url = Some(format!("{}/v1", server.uri()));
+    config.truncation = strategy;
+    
+    let mut llm = LLMInterface::with_config(config);
+    llm.set_catalog(ModelCatalog::from_toml_str(TINY_CATALOG)?);
+    llm.initialize()?;
+    Ok(llm)
+}
+
+/// A conversation of five turns, each about 25 tokens long
+fn long_conversation() -> Conversation {
+    let mut conversation = Conversation::with_system("You are a support bot.");
+    for i in 1..=4 {
+        conversation.add_user(format!("Question {}: my router keeps dropping the connection every few minutes", i));
+        conversation.add_assistant(format!("Answer {}: try restarting it", i));
+    }
+    conversation.add_user("Question 5: it still drops");
+    conversation
+}
+
+async fn sent_messages(server: &MockServer, index: usize) -> Vec<Value> {
+    let requests = server.received_requests().await.unwrap();
+    let body: Value = serde_json::from_slice(&requests[index].body).unwrap();
+    body["messages"].as_array().unwrap().clone()
+}
+
+#[tokio::test]
+async fn test_sliding_window_keeps_recent_turns() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).and(path("/v1/chat/completions"))
+        .respond_with(reply("ok"))
+        .mount(&server)
+        .await;
+    
+    let llm = tiny_llm(&server, TruncationStrategy::SlidingWindow { turns: 2 }).await?;
+    let mut conversation = long_conversation();
+    llm.complete(&mut conversation).await?;
+    
+    let sent = sent_messages(&server, 0).await;
+    assert_eq!(sent.len(), 4);
+    assert_eq!(sent[0]["content"], "You are a support bot.");
+    assert!(sent[1]["content"].as_str().unwrap().starts_with("Question 4"));
+    assert_eq!(sent[3]["content"], "Question 5: it still drops");
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_drop_oldest_pins_system_prompt() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).and(path("/v1/chat/completions"))
+        .respond_with(reply("ok"))
+        .mount(&server)
+        .await;
+    
+    let llm = tiny_llm(&server, TruncationStrategy::DropOldest).await?;
+    let mut conversation = long_conversation();
+    assert!(llm.count_conversation_tokens(&conversation) > 100);
+    
+    llm.complete(&mut conversation).await?;
+    
+    let sent = sent_messages(&server, 0).await;
+    assert_eq!(sent[0]["content"], "You are a support bot.");
+    assert_eq!(sent.last().unwrap()["content"], "Question 5: it still drops");
+    assert!(sent.len() < 10);
+    assert!(!sent.iter().any(|m| m["content"].as_str().unwrap().starts_with("Question 1")));
+    
+    // The conversation itself was trimmed before the reply was appended
+    assert_eq!(conversation.len(), sent.len() + 1);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_summarize_replaces_older_turns() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).and(path("/v1/chat/completions"))
+        .and(body_string_contains("Summarize the conversation"))
+        .respond_with(reply("User's router drops; restarting did not help."))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST")).and(path("/v1/chat/completions"))
+        .respond_with(reply("Let's check the firmware."))
+        .mount(&server)
+        .await;
+    
+    let llm = tiny_llm(&server, TruncationStrategy::Summarize { keep_turns: 1 }).await?;
+    let mut conversation = long_conversation();
+    let completion = llm.complete(&mut conversation).await?;
+    assert_eq!(completion.text(), "Let's check the firmware.");
+    
+    // The summary request carries the transcript of the dropped turns
+    let summary_request = sent_messages(&server, 0).await;
+    assert!(summary_request[1]["content"].as_str().unwrap().contains("User: Question 1"));
+    
+    let sent = sent_messages(&server, 1).await;
+    assert_eq!(sent.len(), 3);
+    assert_eq!(sent[0]["content"], "You are a support bot.");
+    assert!(sent[1]["content"].as_str().unwrap().ends_with("restarting did not help."));
+    assert_eq!(sent[2]["content"], "Question 5: it still drops");
+    
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct Diagnosis {
+    cause: String,
+}
+
+#[tokio::test]
+async fn test_structured_retries_keep_the_question() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).and(path("/v1/chat/completions"))
+        .respond_with(reply("The router is probably overheating."))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST")).and(path("/v1/chat/completions"))
+        .respond_with(reply(r#"{"cause": "overheating"}"#))
+        .mount(&server)
+        .await;
+    
+    let llm = tiny_llm(&server, TruncationStrategy::DropOldest).await?;
+    let mut conversation = long_conversation();
+    let diagnosis: Diagnosis = llm.chat_structured(&mut conversation).await?;
+    assert_eq!(diagnosis.cause, "overheating");
+    
+    // The retry resends the fitted history with the rejected reply and the correction
+    let first = sent_messages(&server, 0).await;
+    let retry = sent_messages(&server, 1).await;
+    assert_eq!(retry.len(), first.len() + 2);
+    assert_eq!(retry[..first.len()], first[..]);
+    assert_eq!(first.last().unwrap()["content"], "Question 5: it still drops");
+    assert_eq!(retry[first.len()]["content"], "The router is probably overheating.");
+    assert!(retry.last().unwrap()["content"].as_str().unwrap().starts_with("Your reply did not match"));
+    
+    Ok(())
+}
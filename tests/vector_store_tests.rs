@@ -0,0 +1,159 @@
+use ai_playground::components::{
+    Document, LLMConfig, Metadata, MetadataFilter, MockProvider, Similarity, VectorStore,
+};
+use ai_playground::prelude::*;
+use ai_playground::Error;
+use anyhow::Result;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn mock_llm() -> Result<LLMInterface> {
+    let mut llm = LLMInterface::with_provider(LLMConfig::new("gpt-4o-mini".to_string()), MockProvider::new());
+    llm.initialize()?;
+    Ok(llm)
+}
+
+fn metadata(value: serde_json::Value) -> Metadata {
+    value.as_object().cloned().unwrap_or_default()
+}
+
+async fn fruit_store(llm: &LLMInterface) -> Result<VectorStore> {
+    let mut store = VectorStore::new();
+    store.add_texts(llm, vec![
+        ("Apples are red or green fruit".to_string(), metadata(json!({"source": "fruit.md", "page": 1}))),
+        ("Bananas are long yellow fruit".to_string(), metadata(json!({"source": "fruit.md", "page": 2}))),
+        ("The sky is blue on a clear day".to_string(), metadata(json!({"source": "sky.txt"}))),
+    ]).await?;
+    Ok(store)
+}
+
+#[tokio::test]
+async fn test_added_texts_are_embedded_and_searchable() -> Result<()> {
+    let llm = mock_llm()?;
+    let mut store = fruit_store(&llm).await?;
+    assert_eq!(store.len(), 3);
+    assert_eq!(store.dimensions(), Some(64));
+    assert_eq!(store.embedding_model(), Some("mock-embedding"));
+    
+    let results = store.search_text(&llm, "Why is the sky blue?", 2, None).await?;
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].document.metadata["source"], "sky.txt");
+    assert!(results[0].score > results[1].score);
+    
+    // Filters apply before ranking
+    let filter = MetadataFilter::equals("source", "fruit.md").and(MetadataFilter::equals("page", 2));
+    let results = store.search_text(&llm, "Why is the sky blue?", 5, Some(&filter)).await?;
+    assert_eq!(results.len(), 1);
+    assert!(results[0].document.text.starts_with("Bananas"));
+    
+    let filter = MetadataFilter::Not(Box::new(MetadataFilter::exists("page")));
+    assert_eq!(store.search_text(&llm, "fruit", 5, Some(&filter)).await?.len(), 1);
+    let filter = MetadataFilter::one_of("page", [1, 3]);
+    assert_eq!(store.search_text(&llm, "fruit", 5, Some(&filter)).await?.len(), 1);
+    
+    let id = store.add_text(&llm, "Cherries are small red fruit", Metadata::new()).await?;
+    assert_eq!(store.get(&id).unwrap().text, "Cherries are small red fruit");
+    assert!(store.remove(&id).is_some());
+    assert_eq!(store.len(), 3);
+    
+    Ok(())
+}
+
+#[test]
+fn test_precomputed_vectors_and_similarity() -> Result<()> {
+    let mut store = VectorStore::new().with_similarity(Similarity::DotProduct);
+    store.insert(Document::new("short", "short", vec![1.0, 0.0]))?;
+    store.insert(Document::new("long", "long", vec![3.0, 1.0]).with_metadata("lang", "en"))?;
+    
+    // Dot product favours long vectors, cosine favours direction
+    let results = store.search(&[1.0, 0.0], 2, None)?;
+    assert_eq!(results[0].document.id, "long");
+    assert_eq!(results[0].score, 3.0);
+    assert_eq!(Similarity::Cosine.score(&[1.0, 0.0], &[1.0, 0.0]), 1.0);
+    assert_eq!(Similarity::Cosine.score(&[1.0, 0.0], &[0.0, 0.0]), 0.0);
+    
+    // Inserting an existing ID replaces the document
+    store.insert(Document::new("short", "replaced", vec![0.0, 1.0]))?;
+    assert_eq!(store.len(), 2);
+    assert_eq!(store.get("short").unwrap().text, "replaced");
+    
+    let error = store.insert(Document::new("wide", "wide", vec![1.0, 2.0, 3.0])).unwrap_err();
+    assert!(matches!(error, Error::DimensionMismatch { expected: 2, actual: 3 }));
+    assert!(matches!(store.search(&[1.0], 1, None), Err(Error::DimensionMismatch { .. })));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_store_persists_to_a_single_file() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("ai-playground-store-{}.json", std::process::id()));
+    let llm = mock_llm()?;
+    let store = fruit_store(&llm).await?.with_path(&path);
+    store.persist()?;
+    
+    let loaded = VectorStore::load(&path)?;
+    assert_eq!(loaded.documents(), store.documents());
+    assert_eq!(loaded.embedding_model(), Some("mock-embedding"));
+    
+    // `initialize` loads the file set with `with_path`
+    let mut reopened = VectorStore::new().with_path(&path);
+    reopened.initialize()?;
+    assert_eq!(reopened.len(), 3);
+    let results = reopened.search_text(&llm, "yellow bananas", 1, None).await?;
+    assert!(results[0].document.text.starts_with("Bananas"));
+    
+    // Generated IDs continue after the loaded ones
+    let id = reopened.add_text(&llm, "Grapes grow in bunches", Metadata::new()).await?;
+    assert_eq!(id, "doc-4");
+    assert_eq!(reopened.len(), 4);
+    assert!(store.get(&id).is_none());
+    
+    std::fs::remove_file(&path)?;
+    assert!(matches!(VectorStore::load(&path), Err(Error::VectorStore(_))));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_store_rejects_another_embedding_model() -> Result<()> {
+    let llm = mock_llm()?;
+    let store = fruit_store(&llm).await?;
+    
+    let mut other = mock_llm()?;
+    other.set_embedding_model("mock-embedding-large".to_string(), None);
+    let error = store.search_text(&other, "fruit", 1, None).await.unwrap_err();
+    assert!(matches!(error, Error::VectorStore(_)));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_batch_with_a_bad_vector_adds_nothing() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/embeddings"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [
+                {"index": 0, "embedding": [1.0, 0.0]},
+                {"index": 1, "embedding": [0.0, 1.0, 0.0]}
+            ]
+        })))
+        .mount(&server)
+        .await;
+    
+    let mut config = LLMConfig::new("gpt-4o-mini".to_string());
+    config.base_url = Some(server.uri());
+    config.api_key = Some("test-key".to_string());
+    let mut llm = LLMInterface::with_config(config);
+    llm.initialize()?;
+    
+    let mut store = VectorStore::new();
+    let texts = vec![("first".to_string(), Metadata::new()), ("second".to_string(), Metadata::new())];
+    let error = store.add_texts(&llm, texts).await.unwrap_err();
+    assert!(matches!(error, Error::DimensionMismatch { expected: 2, actual: 3 }));
+    assert!(store.is_empty());
+    assert_eq!(store.embedding_model(), None);
+    
+    Ok(())
+}